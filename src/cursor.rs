@@ -0,0 +1,431 @@
+//! Mouse cursors for egui's `CursorIcon`s.
+//!
+//! GLFW only has standard cursors for a handful of shapes, so the others are drawn here
+//! as black shapes with a white outline.
+
+use egui::{pos2, CursorIcon, Pos2, Vec2};
+
+/// Width and height of generated cursor images, in pixels.
+const CURSOR_SIZE: u32 = 32;
+
+/// Width of the white outline around generated cursors, in pixels.
+const OUTLINE: f32 = 1.5;
+
+/// Applies `egui::PlatformOutput::cursor_icon` to a window.
+///
+/// Cursors are created the first time an icon is shown and kept for later frames.
+/// `CursorIcon::None` hides the cursor while it is over the window.
+#[derive(Debug, Default)]
+pub struct CursorManager {
+    /// Icon last applied to the window.
+    current: Option<CursorIcon>,
+
+    /// Icon whose cursor the window holds, the window owns it until it is replaced.
+    shown: Option<CursorIcon>,
+
+    /// Whether the cursor mode was set to hidden for `CursorIcon::None`.
+    hidden: bool,
+
+    cursors: Vec<(CursorIcon, glfw::Cursor)>,
+}
+
+impl CursorManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Shows `icon` while the cursor is over `window`, does nothing if it already is.
+    pub fn set_cursor(&mut self, window: &mut glfw::Window, icon: CursorIcon) {
+        if self.current == Some(icon) {
+            return;
+        }
+        self.current = Some(icon);
+
+        // Only hide a normal cursor, and only undo our own hiding, the application may
+        // have hidden or disabled the cursor itself, e.g. to capture it.
+        if icon == CursorIcon::None {
+            if window.get_cursor_mode() == glfw::CursorMode::Normal {
+                window.set_cursor_mode(glfw::CursorMode::Hidden);
+                self.hidden = true;
+            }
+            return;
+        }
+
+        if self.hidden {
+            window.set_cursor_mode(glfw::CursorMode::Normal);
+            self.hidden = false;
+        }
+
+        // The default icon is the system's default cursor, which needs no cursor object.
+        let cursor = (icon != CursorIcon::Default).then(|| self.take_cursor(icon));
+        let shown = std::mem::replace(&mut self.shown, cursor.as_ref().map(|_| icon));
+
+        if let (Some(previous), Some(shown)) = (window.set_cursor(cursor), shown) {
+            self.cursors.push((shown, previous));
+        }
+    }
+
+    /// Takes the cursor for `icon` out of the cache, creating it if there is none.
+    fn take_cursor(&mut self, icon: CursorIcon) -> glfw::Cursor {
+        match self.cursors.iter().position(|(cached, _)| *cached == icon) {
+            Some(index) => self.cursors.swap_remove(index).1,
+            None => create_cursor(icon),
+        }
+    }
+}
+
+/// Creates a cursor for `icon`, using GLFW's standard cursor if it has one.
+pub fn create_cursor(icon: CursorIcon) -> glfw::Cursor {
+    match crate::standard_cursor(icon) {
+        Some(standard) => glfw::Cursor::standard(standard),
+        None => {
+            let (image, (x_hotspot, y_hotspot)) = cursor_image(icon);
+            glfw::Cursor::create_from_pixels(image, x_hotspot, y_hotspot)
+        }
+    }
+}
+
+/// Draws the cursor for `icon`, returning the image and its hotspot.
+///
+/// `CursorIcon::None` is fully transparent, icons without a drawing of their own
+/// get the usual arrow.
+pub fn cursor_image(icon: CursorIcon) -> (glfw::PixelImage, (u32, u32)) {
+    let (shapes, hotspot) = cursor_shapes(icon);
+
+    let pixels = (0..CURSOR_SIZE * CURSOR_SIZE)
+        .map(|i| {
+            let p = pos2(
+                (i % CURSOR_SIZE) as f32 + 0.5,
+                (i / CURSOR_SIZE) as f32 + 0.5,
+            );
+            let distance = shapes
+                .iter()
+                .map(|shape| shape.distance(p))
+                .fold(f32::INFINITY, f32::min);
+
+            // Black inside the shapes, white in the outline around them, antialiased.
+            let black = (0.5 - distance).clamp(0.0, 1.0);
+            let alpha = (OUTLINE + 0.5 - distance).clamp(0.0, 1.0);
+            let grey = if alpha > 0.0 {
+                (255.0 * (1.0 - black / alpha)).round() as u8
+            } else {
+                0
+            };
+            u32::from_ne_bytes([grey, grey, grey, (255.0 * alpha).round() as u8])
+        })
+        .collect();
+
+    let image = glfw::PixelImage {
+        width: CURSOR_SIZE,
+        height: CURSOR_SIZE,
+        pixels,
+    };
+    (image, (hotspot.x as u32, hotspot.y as u32))
+}
+
+/// Part of a cursor drawing, in pixels from the top left of the image.
+enum Shape {
+    /// Line from `a` to `b`, `radius` thick on either side.
+    Line { a: Pos2, b: Pos2, radius: f32 },
+
+    /// Circle through the middle of a line `radius` thick on either side.
+    Ring {
+        center: Pos2,
+        circle_radius: f32,
+        radius: f32,
+    },
+
+    /// Filled polygon.
+    Fill(Vec<Pos2>),
+}
+
+impl Shape {
+    /// Distance from `p` to the edge of the shape, negative inside it.
+    fn distance(&self, p: Pos2) -> f32 {
+        match self {
+            Shape::Line { a, b, radius } => segment_distance(p, *a, *b) - radius,
+            Shape::Ring {
+                center,
+                circle_radius,
+                radius,
+            } => (center.distance(p) - circle_radius).abs() - radius,
+            Shape::Fill(points) => {
+                let edges = points.iter().zip(points.iter().cycle().skip(1));
+
+                let mut inside = false;
+                let mut distance = f32::INFINITY;
+                for (a, b) in edges {
+                    if (a.y > p.y) != (b.y > p.y)
+                        && p.x < a.x + (p.y - a.y) / (b.y - a.y) * (b.x - a.x)
+                    {
+                        inside = !inside;
+                    }
+                    distance = distance.min(segment_distance(p, *a, *b));
+                }
+
+                if inside {
+                    -distance
+                } else {
+                    distance
+                }
+            }
+        }
+    }
+}
+
+fn segment_distance(p: Pos2, a: Pos2, b: Pos2) -> f32 {
+    let ab = b - a;
+    let t = if ab.length_sq() > 0.0 {
+        ((p - a).dot(ab) / ab.length_sq()).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (a + ab * t).distance(p)
+}
+
+fn line(a: Pos2, b: Pos2, radius: f32) -> Shape {
+    Shape::Line { a, b, radius }
+}
+
+fn dot(center: Pos2, radius: f32) -> Shape {
+    line(center, center, radius)
+}
+
+/// The usual arrow, pointing at (3, 3).
+fn pointer() -> Shape {
+    Shape::Fill(vec![
+        pos2(3.0, 3.0),
+        pos2(3.0, 22.0),
+        pos2(8.0, 17.0),
+        pos2(11.0, 24.0),
+        pos2(14.0, 23.0),
+        pos2(11.0, 16.0),
+        pos2(17.0, 16.0),
+    ])
+}
+
+fn arrow_head(tip: Pos2, direction: Vec2) -> Shape {
+    let direction = direction.normalized();
+    let base = tip - direction * 6.0;
+    let side = direction.rot90() * 4.5;
+    Shape::Fill(vec![tip, base + side, base - side])
+}
+
+fn arrow(from: Pos2, to: Pos2) -> Vec<Shape> {
+    let direction = (to - from).normalized();
+    vec![
+        line(from, to - direction * 4.0, 1.0),
+        arrow_head(to, direction),
+    ]
+}
+
+fn double_arrow(a: Pos2, b: Pos2) -> Vec<Shape> {
+    let middle = a + (b - a) / 2.0;
+    let mut shapes = arrow(middle, a);
+    shapes.extend(arrow(middle, b));
+    shapes
+}
+
+fn hourglass(center: Pos2, half_size: f32) -> Vec<Shape> {
+    let (left, right) = (center.x - half_size, center.x + half_size);
+    let (top, bottom) = (center.y - half_size, center.y + half_size);
+    vec![
+        Shape::Fill(vec![pos2(left, top), pos2(right, top), center]),
+        Shape::Fill(vec![center, pos2(left, bottom), pos2(right, bottom)]),
+    ]
+}
+
+fn not_allowed(center: Pos2, circle_radius: f32, radius: f32) -> Vec<Shape> {
+    let diagonal = Vec2::splat(circle_radius * std::f32::consts::FRAC_1_SQRT_2);
+    vec![
+        Shape::Ring {
+            center,
+            circle_radius,
+            radius,
+        },
+        line(center - diagonal, center + diagonal, radius),
+    ]
+}
+
+fn hand(finger_top: f32) -> Vec<Shape> {
+    let mut shapes: Vec<Shape> = [10.0, 14.0, 18.0, 22.0]
+        .iter()
+        .map(|&x| line(pos2(x, finger_top), pos2(x, 16.0), 1.4))
+        .collect();
+    shapes.push(Shape::Fill(vec![
+        pos2(8.6, 15.0),
+        pos2(23.4, 15.0),
+        pos2(23.4, 22.0),
+        pos2(20.0, 26.0),
+        pos2(12.0, 26.0),
+        pos2(8.6, 22.0),
+    ]));
+    shapes.push(line(pos2(9.0, 21.0), pos2(5.5, 16.5), 1.4));
+    shapes
+}
+
+fn magnifier(plus: bool) -> Vec<Shape> {
+    let center = pos2(13.0, 13.0);
+    let mut shapes = vec![
+        Shape::Ring {
+            center,
+            circle_radius: 7.5,
+            radius: 1.5,
+        },
+        line(pos2(19.0, 19.0), pos2(26.0, 26.0), 2.0),
+        line(pos2(9.5, 13.0), pos2(16.5, 13.0), 1.0),
+    ];
+    if plus {
+        shapes.push(line(pos2(13.0, 9.5), pos2(13.0, 16.5), 1.0));
+    }
+    shapes
+}
+
+/// Shapes drawing `icon`, and the hotspot among them.
+fn cursor_shapes(icon: CursorIcon) -> (Vec<Shape>, Pos2) {
+    let center = pos2(16.0, 16.0);
+    let tip = pos2(3.0, 3.0);
+
+    // Badges next to the arrow are drawn around this point.
+    let badge = pos2(24.0, 24.0);
+
+    match icon {
+        CursorIcon::None => (vec![], tip),
+
+        CursorIcon::ContextMenu => {
+            let mut shapes = vec![pointer()];
+            for y in [20.0, 24.0, 28.0] {
+                shapes.push(line(pos2(19.0, y), pos2(28.0, y), 1.0));
+            }
+            (shapes, tip)
+        }
+        CursorIcon::Help => {
+            let points = [
+                pos2(20.0, 17.0),
+                pos2(22.0, 15.0),
+                pos2(26.0, 15.0),
+                pos2(28.0, 17.0),
+                pos2(28.0, 19.0),
+                pos2(24.0, 22.0),
+                pos2(24.0, 24.0),
+            ];
+            let mut shapes = vec![pointer(), dot(pos2(24.0, 27.5), 1.3)];
+            shapes.extend(points.windows(2).map(|w| line(w[0], w[1], 1.1)));
+            (shapes, tip)
+        }
+        CursorIcon::Progress => {
+            let mut shapes = vec![pointer()];
+            shapes.extend(hourglass(badge, 5.0));
+            (shapes, tip)
+        }
+        CursorIcon::Wait => (hourglass(center, 9.0), center),
+
+        CursorIcon::Cell => (
+            vec![
+                line(pos2(16.0, 7.0), pos2(16.0, 25.0), 2.5),
+                line(pos2(7.0, 16.0), pos2(25.0, 16.0), 2.5),
+            ],
+            center,
+        ),
+        CursorIcon::VerticalText => (
+            vec![
+                line(pos2(8.0, 16.0), pos2(24.0, 16.0), 1.0),
+                line(pos2(8.0, 12.0), pos2(8.0, 20.0), 1.0),
+                line(pos2(24.0, 12.0), pos2(24.0, 20.0), 1.0),
+            ],
+            center,
+        ),
+
+        CursorIcon::Alias => {
+            let mut shapes = vec![pointer(), line(pos2(20.0, 27.0), pos2(20.0, 22.0), 1.0)];
+            shapes.extend(arrow(pos2(20.0, 22.0), pos2(29.0, 22.0)));
+            (shapes, tip)
+        }
+        CursorIcon::Copy => (
+            vec![
+                pointer(),
+                line(pos2(24.0, 20.0), pos2(24.0, 28.0), 1.2),
+                line(pos2(20.0, 24.0), pos2(28.0, 24.0), 1.2),
+            ],
+            tip,
+        ),
+        CursorIcon::Move => {
+            let mut shapes = double_arrow(pos2(16.0, 4.0), pos2(16.0, 28.0));
+            shapes.extend(double_arrow(pos2(4.0, 16.0), pos2(28.0, 16.0)));
+            (shapes, center)
+        }
+        CursorIcon::NoDrop => {
+            let mut shapes = vec![pointer()];
+            shapes.extend(not_allowed(badge, 4.5, 1.0));
+            (shapes, tip)
+        }
+        CursorIcon::NotAllowed => (not_allowed(center, 9.0, 1.5), center),
+        CursorIcon::Grab => (hand(8.0), center),
+        CursorIcon::Grabbing => (hand(13.0), center),
+
+        CursorIcon::AllScroll => (
+            vec![
+                dot(center, 2.5),
+                arrow_head(pos2(16.0, 4.0), Vec2::UP),
+                arrow_head(pos2(16.0, 28.0), Vec2::DOWN),
+                arrow_head(pos2(4.0, 16.0), Vec2::LEFT),
+                arrow_head(pos2(28.0, 16.0), Vec2::RIGHT),
+            ],
+            center,
+        ),
+
+        CursorIcon::ResizeNeSw | CursorIcon::ResizeNorthEast | CursorIcon::ResizeSouthWest => {
+            (double_arrow(pos2(24.0, 8.0), pos2(8.0, 24.0)), center)
+        }
+        CursorIcon::ResizeNwSe | CursorIcon::ResizeNorthWest | CursorIcon::ResizeSouthEast => {
+            (double_arrow(pos2(8.0, 8.0), pos2(24.0, 24.0)), center)
+        }
+        CursorIcon::ResizeHorizontal
+        | CursorIcon::ResizeEast
+        | CursorIcon::ResizeWest
+        | CursorIcon::ResizeColumn => (double_arrow(pos2(4.0, 16.0), pos2(28.0, 16.0)), center),
+        CursorIcon::ResizeVertical
+        | CursorIcon::ResizeNorth
+        | CursorIcon::ResizeSouth
+        | CursorIcon::ResizeRow => (double_arrow(pos2(16.0, 4.0), pos2(16.0, 28.0)), center),
+
+        CursorIcon::ZoomIn => (magnifier(true), pos2(13.0, 13.0)),
+        CursorIcon::ZoomOut => (magnifier(false), pos2(13.0, 13.0)),
+
+        _ => (vec![pointer()], tip),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draws_every_icon_without_a_standard_cursor() {
+        for icon in CursorIcon::ALL {
+            if icon == CursorIcon::None || crate::standard_cursor(icon).is_some() {
+                continue;
+            }
+
+            let (image, (x, y)) = cursor_image(icon);
+            assert!(x < image.width && y < image.height, "{:?}", icon);
+
+            let alpha = |pixel: &u32| pixel.to_ne_bytes()[3];
+            assert!(image.pixels.iter().any(|p| alpha(p) == 255), "{:?}", icon);
+
+            // The outline must fit inside the image.
+            let size = image.width as usize;
+            let mut edges = image.pixels.iter().enumerate().filter(|(i, _)| {
+                let (x, y) = (i % size, i / size);
+                x == 0 || y == 0 || x == size - 1 || y == size - 1
+            });
+            assert!(edges.all(|(_, p)| alpha(p) == 0), "{:?}", icon);
+        }
+    }
+
+    #[test]
+    fn none_is_transparent() {
+        let (image, _) = cursor_image(CursorIcon::None);
+        assert!(image.pixels.iter().all(|p| p.to_ne_bytes()[3] == 0));
+    }
+}
@@ -0,0 +1,129 @@
+//! Everything needed to run egui in a GLFW window, wired together.
+
+use crate::{copy_to_clipboard, route_event, CursorManager, EguiInputState, Painter, UrlOpener};
+use std::time::Duration;
+
+/// Owns the egui context along with the input state and painter for one window.
+///
+/// A frame is [`Self::run`] followed by [`Self::paint`], with every window event
+/// passed to [`Self::on_event`]:
+///
+/// ```no_run
+/// # let mut glfw = glfw::init(glfw::FAIL_ON_ERRORS).unwrap();
+/// # let (mut window, events) = glfw
+/// #     .create_window(800, 600, "egui", glfw::WindowMode::Windowed)
+/// #     .unwrap();
+/// let mut egui_glfw = egui_glfw_gl::EguiGlfw::new(&mut window);
+///
+/// while !window.should_close() {
+///     egui_glfw.run(&mut window, |ctx| {
+///         egui::CentralPanel::default().show(ctx, |ui| ui.label("Hello"));
+///     });
+///     egui_glfw.paint();
+///
+///     for (_, event) in glfw::flush_messages(&events) {
+///         egui_glfw.on_event(&window, event);
+///     }
+///     glfw::Context::swap_buffers(&mut window);
+///     glfw.poll_events();
+/// }
+/// ```
+pub struct EguiGlfw {
+    pub ctx: egui::Context,
+    pub input_state: EguiInputState,
+    pub painter: Painter,
+    pub cursor_manager: CursorManager,
+    pub url_opener: UrlOpener,
+
+    /// Output of the last [`Self::run`], waiting for [`Self::paint`].
+    shapes: Vec<egui::epaint::ClippedShape>,
+    textures_delta: egui::TexturesDelta,
+}
+
+impl EguiGlfw {
+    /// Sets up egui for `window`, whose GL context must be current and loaded.
+    pub fn new(window: &mut glfw::Window) -> Self {
+        Self {
+            ctx: egui::Context::default(),
+            input_state: EguiInputState::from_window(window),
+            painter: Painter::new(window),
+            cursor_manager: CursorManager::new(),
+            url_opener: UrlOpener::new(),
+            shapes: Vec::new(),
+            textures_delta: Default::default(),
+        }
+    }
+
+    /// Passes a window event to egui, returning whether egui consumed it, see [`route_event`].
+    pub fn on_event(&mut self, window: &glfw::Window, event: glfw::WindowEvent) -> bool {
+        if let glfw::WindowEvent::CursorEnter(true) = event {
+            self.input_state.sync_pointer_pos(window);
+        }
+
+        route_event(event, &mut self.input_state)
+    }
+
+    /// Runs `run_ui` for a frame and applies its platform output to `window`.
+    ///
+    /// Returns how long egui can wait before it needs another frame, as
+    /// `egui::FullOutput::repaint_after`.
+    pub fn run(
+        &mut self,
+        window: &mut glfw::Window,
+        run_ui: impl FnOnce(&egui::Context),
+    ) -> Duration {
+        let egui::FullOutput {
+            platform_output,
+            repaint_after,
+            textures_delta,
+            shapes,
+        } = self.ctx.run(self.input_state.take(), run_ui);
+        self.input_state.update_wants(&self.ctx);
+
+        self.handle_platform_output(window, platform_output);
+
+        // Texture changes must all be applied even if a frame is never painted.
+        self.shapes = shapes;
+        self.textures_delta.append(textures_delta);
+
+        repaint_after
+    }
+
+    /// Paints the frame from the last [`Self::run`].
+    ///
+    /// Uses the scale egui laid the frame out at, which is the window's content scale
+    /// unless the application changed it with `egui::Context::set_pixels_per_point`.
+    pub fn paint(&mut self) {
+        let clipped_primitives = self.ctx.tessellate(std::mem::take(&mut self.shapes));
+        let textures_delta = std::mem::take(&mut self.textures_delta);
+
+        self.painter.paint_and_update_textures(
+            self.ctx.pixels_per_point(),
+            &clipped_primitives,
+            &textures_delta,
+        );
+    }
+
+    fn handle_platform_output(
+        &mut self,
+        window: &mut glfw::Window,
+        platform_output: egui::PlatformOutput,
+    ) {
+        // GLFW has no screen reader or IME support to pass the rest of the output to.
+        let egui::PlatformOutput {
+            cursor_icon,
+            open_url,
+            copied_text,
+            events: _,
+            mutable_text_under_cursor: _,
+            text_cursor_pos: _,
+        } = platform_output;
+
+        self.cursor_manager.set_cursor(window, cursor_icon);
+        self.url_opener.handle(open_url);
+
+        if !copied_text.is_empty() {
+            copy_to_clipboard(&mut self.input_state, copied_text);
+        }
+    }
+}
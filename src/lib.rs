@@ -5,12 +5,21 @@
 pub use egui;
 pub use gl;
 pub use glfw;
+#[cfg(feature = "image")]
+pub use image;
 
+pub mod cursor;
+pub mod integration;
+pub mod open_url;
 pub mod painter;
 
+pub use cursor::CursorManager;
+pub use integration::EguiGlfw;
+pub use open_url::UrlOpener;
 pub use painter::Painter;
 
 use egui::*;
+use std::collections::HashSet;
 
 #[cfg(not(feature = "clipboard"))]
 mod clipboard;
@@ -20,22 +29,478 @@ use clipboard::{
     ClipboardProvider,
 };
 
+/// Back/forward navigation requested with the thumb buttons of a mouse.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NavigationEvent {
+    Back,
+    Forward,
+}
+
+/// Mouse buttons that are reported as [`NavigationEvent`]s.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NavigationButtons {
+    pub back: glfw::MouseButton,
+    pub forward: glfw::MouseButton,
+}
+
+impl Default for NavigationButtons {
+    fn default() -> Self {
+        Self {
+            back: glfw::MouseButton::Button4,
+            forward: glfw::MouseButton::Button5,
+        }
+    }
+}
+
+/// How GLFW scroll offsets are turned into egui scroll and zoom events.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScrollSettings {
+    /// Points scrolled per notch of a mouse wheel.
+    pub line_points: f32,
+
+    /// Points scrolled per unit of smooth scrolling, e.g. from a trackpad.
+    /// GLFW doesn't say where offsets come from, so whole-number offsets are
+    /// treated as wheel notches and fractional ones as smooth scrolling.
+    pub smooth_points: f32,
+
+    /// Shift+wheel scrolls horizontally.
+    pub shift_scrolls_horizontally: bool,
+
+    /// Ctrl+wheel (or the command modifier) zooms instead of scrolling.
+    pub command_zooms: bool,
+
+    /// Points of scrolling that zoom by a factor of `e`.
+    pub zoom_points: f32,
+}
+
+impl Default for ScrollSettings {
+    fn default() -> Self {
+        Self {
+            line_points: 50.0,
+            smooth_points: 10.0,
+            shift_scrolls_horizontally: true,
+            command_zooms: true,
+            zoom_points: 200.0,
+        }
+    }
+}
+
+impl ScrollSettings {
+    /// Scroll delta in points for a GLFW scroll offset.
+    pub fn scroll_delta(&self, x: f64, y: f64) -> egui::Vec2 {
+        let notched = x.fract() == 0.0 && y.fract() == 0.0;
+        let points = if notched {
+            self.line_points
+        } else {
+            self.smooth_points
+        };
+
+        vec2(x as f32, y as f32) * points
+    }
+}
+
+/// Clipboard operation triggered by a keyboard shortcut.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClipboardAction {
+    Cut,
+    Copy,
+    Paste,
+}
+
+/// A key that triggers `action` when pressed with exactly `modifiers`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ClipboardShortcut {
+    pub modifiers: Modifiers,
+    pub key: egui::Key,
+    pub action: ClipboardAction,
+}
+
+impl ClipboardShortcut {
+    pub fn new(modifiers: Modifiers, key: egui::Key, action: ClipboardAction) -> Self {
+        Self {
+            modifiers,
+            key,
+            action,
+        }
+    }
+
+    /// Command+X/C/V, plus the Shift+Delete, Ctrl+Insert and Shift+Insert alternatives.
+    pub fn defaults() -> Vec<Self> {
+        use ClipboardAction::*;
+
+        vec![
+            Self::new(Modifiers::COMMAND, Key::X, Cut),
+            Self::new(Modifiers::COMMAND, Key::C, Copy),
+            Self::new(Modifiers::COMMAND, Key::V, Paste),
+            Self::new(Modifiers::SHIFT, Key::Delete, Cut),
+            Self::new(Modifiers::CTRL, Key::Insert, Copy),
+            Self::new(Modifiers::SHIFT, Key::Insert, Paste),
+        ]
+    }
+}
+
+/// Where [`EguiInputState::take`] gets `RawInput::time` and `predicted_dt` from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FrameClock {
+    /// Read GLFW's timer each frame, and predict `predicted_dt` from the refresh rate
+    /// of the monitor the window is on, refined by the intervals between frames.
+    #[default]
+    Glfw,
+
+    /// Only advance time with [`EguiInputState::advance_time`], for reproducible runs
+    /// such as tests and recordings.
+    Manual,
+}
+
+/// Frame intervals longer than this are idle gaps, not a sign of the frame rate.
+const MAX_MEASURED_DT: f32 = 0.25;
+
+/// Which GLFW modifier egui sees as `command`, the modifier used for shortcuts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommandModifier {
+    /// Ctrl is command, as on Windows and Linux.
+    Ctrl,
+
+    /// Super (⌘ on macOS) is command, and is also reported as `mac_cmd`.
+    Super,
+
+    /// Either Ctrl or Super is command, Super is also reported as `mac_cmd`.
+    Either,
+}
+
+impl Default for CommandModifier {
+    /// Super on macOS, Ctrl elsewhere.
+    fn default() -> Self {
+        if cfg!(target_os = "macos") {
+            Self::Super
+        } else {
+            Self::Ctrl
+        }
+    }
+}
+
+/// What egui wanted at the end of the last frame, used to route events.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct InputWants {
+    /// The pointer is over an egui area, or egui is using it (e.g. dragging a window).
+    pub pointer: bool,
+
+    /// A widget has keyboard focus.
+    pub keyboard: bool,
+}
+
 pub struct EguiInputState {
     pub pointer_pos: Pos2,
     pub clipboard: Option<ClipboardContext>,
     pub input: RawInput,
     pub modifiers: Modifiers,
+
+    /// Mouse buttons that push to [`Self::navigation_events`] when pressed, disabled by default.
+    pub navigation_buttons: Option<NavigationButtons>,
+
+    /// Navigation requested since the application last drained it.
+    pub navigation_events: Vec<NavigationEvent>,
+
+    /// Read the contents of dropped files into `DroppedFile::bytes`, disabled by default.
+    pub read_dropped_files: bool,
+
+    pub scroll: ScrollSettings,
+
+    /// Modifier reported to egui as `command` and `mac_cmd`.
+    pub command_modifier: CommandModifier,
+
+    /// Map letter keys by the character they produce in the current keyboard layout
+    /// rather than by their position on a US keyboard, see [`translate_key_with_layout`].
+    /// Disabled by default.
+    pub layout_aware_keys: bool,
+
+    /// Shortcuts that send cut, copy and paste events to egui, instead of the key events
+    /// for the keys that trigger them.
+    pub clipboard_shortcuts: Vec<ClipboardShortcut>,
+
+    /// Content scale of the window, kept in sync with `glfw::WindowEvent::ContentScale`.
+    pub pixels_per_point: f32,
+
+    /// Framebuffer size in pixels, kept in sync with `glfw::WindowEvent::FramebufferSize`.
+    pub framebuffer_size: (u32, u32),
+
+    /// Window size in screen coordinates, kept in sync with `glfw::WindowEvent::Size`.
+    /// Cursor positions are in screen coordinates, which differ from pixels on macOS.
+    pub window_size: (u32, u32),
+
+    /// Updated with [`Self::update_wants`], see [`route_event`].
+    pub wants: InputWants,
+
+    pub clock: FrameClock,
+
+    /// Time of the last frame, in seconds.
+    frame_time: Option<f64>,
+
+    /// Window position in screen coordinates, kept in sync with `glfw::WindowEvent::Pos`
+    /// to find the monitor the window is on.
+    window_pos: (i32, i32),
+    refresh_rate: Option<u32>,
+
+    /// Keys and buttons egui has seen pressed but not released, so they can be
+    /// released when the window loses focus.
+    pressed_keys: HashSet<egui::Key>,
+    pressed_buttons: Vec<egui::PointerButton>,
 }
 
 impl EguiInputState {
     pub fn new(input: RawInput) -> Self {
+        let pixels_per_point = input.pixels_per_point.unwrap_or(1.0);
+        let framebuffer_size = input
+            .screen_rect
+            .map(|rect| {
+                let size = rect.size() * pixels_per_point;
+                (size.x.round() as u32, size.y.round() as u32)
+            })
+            .unwrap_or_default();
+
         EguiInputState {
             pointer_pos: Pos2::new(0f32, 0f32),
             clipboard: init_clipboard(),
             input,
             modifiers: Modifiers::default(),
+            navigation_buttons: None,
+            navigation_events: Vec::new(),
+            read_dropped_files: false,
+            scroll: ScrollSettings::default(),
+            command_modifier: CommandModifier::default(),
+            layout_aware_keys: false,
+            clipboard_shortcuts: ClipboardShortcut::defaults(),
+            pixels_per_point,
+            framebuffer_size,
+            window_size: framebuffer_size,
+            wants: InputWants::default(),
+            clock: FrameClock::default(),
+            frame_time: None,
+            window_pos: (0, 0),
+            refresh_rate: None,
+            pressed_keys: HashSet::new(),
+            pressed_buttons: Vec::new(),
+        }
+    }
+
+    /// Creates a state ready to use with `window`.
+    ///
+    /// Enables polling for every event [`handle_event`] understands, and seeds the screen
+    /// size, `pixels_per_point`, focus and pointer position from the live window. The
+    /// window's GL context must be current and loaded, to query `max_texture_side`.
+    pub fn from_window(window: &mut glfw::Window) -> Self {
+        window.set_char_polling(true);
+        window.set_key_polling(true);
+        window.set_mouse_button_polling(true);
+        window.set_cursor_pos_polling(true);
+        window.set_cursor_enter_polling(true);
+        window.set_scroll_polling(true);
+        window.set_focus_polling(true);
+        window.set_drag_and_drop_polling(true);
+        window.set_framebuffer_size_polling(true);
+        window.set_pos_polling(true);
+        window.set_size_polling(true);
+        window.set_content_scale_polling(true);
+
+        let mut max_texture_side = 0;
+        unsafe {
+            gl::GetIntegerv(gl::MAX_TEXTURE_SIZE, &mut max_texture_side);
+        }
+
+        let mut state = Self::new(RawInput {
+            max_texture_side: (max_texture_side > 0).then_some(max_texture_side as usize),
+            has_focus: window.is_focused(),
+            ..Default::default()
+        });
+
+        let (width, height) = window.get_framebuffer_size();
+        state.framebuffer_size = (width.max(0) as u32, height.max(0) as u32);
+        let (width, height) = window.get_size();
+        state.window_size = (width.max(0) as u32, height.max(0) as u32);
+        state.pixels_per_point = window.get_content_scale().0;
+        state.update_screen_rect();
+
+        state.window_pos = window.get_pos();
+        state.update_refresh_rate();
+
+        state.sync_pointer_pos(window);
+
+        state
+    }
+
+    /// Records what egui wants after `egui::Context::end_frame`, for [`route_event`].
+    pub fn update_wants(&mut self, ctx: &egui::Context) {
+        self.wants = InputWants {
+            pointer: ctx.wants_pointer_input() || ctx.is_pointer_over_area(),
+            keyboard: ctx.wants_keyboard_input(),
+        };
+    }
+
+    /// Whether `event` belongs to egui rather than the application, based on [`Self::wants`].
+    ///
+    /// Releases are never consumed, so the application sees the release of every key
+    /// and button it saw pressed. Window-level events are never consumed either.
+    pub fn consumes(&self, event: &glfw::WindowEvent) -> bool {
+        use glfw::WindowEvent::*;
+
+        match event {
+            MouseButton(_, glfw::Action::Release, _) => false,
+            MouseButton(..) | CursorPos(..) | Scroll(..) | FileDrop(..) => self.wants.pointer,
+
+            Key(_, _, glfw::Action::Release, _) => false,
+            Key(..) | Char(..) | CharModifiers(..) => self.wants.keyboard,
+
+            _ => false,
+        }
+    }
+
+    /// Moves the pointer to the cursor position of `window`.
+    ///
+    /// Call this when the cursor enters the window (`glfw::WindowEvent::CursorEnter(true)`),
+    /// so egui hovers the right widget before the cursor moves again.
+    pub fn sync_pointer_pos(&mut self, window: &glfw::Window) {
+        let (x, y) = window.get_cursor_pos();
+        self.set_pointer_pos(x, y);
+    }
+
+    /// Takes the input gathered since the last frame, to pass to `egui::Context::begin_frame`.
+    ///
+    /// Unlike `RawInput::take`, this always includes the current `pixels_per_point`
+    /// and `screen_rect`, which egui otherwise forgets after each frame.
+    pub fn take(&mut self) -> RawInput {
+        self.update_screen_rect();
+        self.update_time();
+        self.input.take()
+    }
+
+    /// Advances time by `dt` seconds for the next frame, starting from zero.
+    ///
+    /// Use with [`FrameClock::Manual`], so animations step by the same amount however
+    /// long frames actually take.
+    pub fn advance_time(&mut self, dt: f32) {
+        let time = self.frame_time.unwrap_or(0.0) + dt as f64;
+        self.frame_time = Some(time);
+        self.input.time = Some(time);
+        self.input.predicted_dt = dt;
+    }
+
+    fn update_time(&mut self) {
+        if self.clock == FrameClock::Manual {
+            // Frames without `advance_time` happen at the same instant.
+            self.input.time = Some(self.frame_time.unwrap_or(0.0));
+            return;
+        }
+
+        let now = unsafe { glfw::ffi::glfwGetTime() };
+        if let Some(last) = self.frame_time {
+            let dt = (now - last) as f32;
+            if dt > 0.0 && dt <= MAX_MEASURED_DT {
+                self.input.predicted_dt += (dt - self.input.predicted_dt) * 0.2;
+            }
+        }
+        self.frame_time = Some(now);
+        self.input.time = Some(now);
+    }
+
+    /// Predicts frames to take as long as a refresh of the monitor the window is now on,
+    /// if it changed.
+    fn update_refresh_rate(&mut self) {
+        if self.clock == FrameClock::Manual {
+            return;
+        }
+
+        let refresh_rate = monitor_refresh_rate(self.window_pos, self.window_size);
+        if refresh_rate != self.refresh_rate {
+            self.refresh_rate = refresh_rate;
+            if let Some(refresh_rate) = refresh_rate {
+                self.input.predicted_dt = 1.0 / refresh_rate as f32;
+            }
+        }
+    }
+
+    fn update_screen_rect(&mut self) {
+        self.input.pixels_per_point = Some(self.pixels_per_point);
+
+        // Minimized windows report an empty framebuffer, keep the last size for those.
+        let (width, height) = self.framebuffer_size;
+        if width > 0 && height > 0 {
+            self.input.screen_rect = Some(Rect::from_min_size(
+                Pos2::new(0f32, 0f32),
+                egui::vec2(width as f32, height as f32) / self.pixels_per_point,
+            ));
+        }
+    }
+
+    /// Pixels per screen coordinate, which is 1 everywhere but on macOS Retina displays.
+    fn framebuffer_scale(&self) -> egui::Vec2 {
+        let (fb_width, fb_height) = self.framebuffer_size;
+        let (width, height) = self.window_size;
+        if fb_width > 0 && fb_height > 0 && width > 0 && height > 0 {
+            egui::vec2(
+                fb_width as f32 / width as f32,
+                fb_height as f32 / height as f32,
+            )
+        } else {
+            egui::vec2(1.0, 1.0)
+        }
+    }
+
+    fn set_pointer_pos(&mut self, x: f64, y: f64) {
+        let scale = self.framebuffer_scale() / self.pixels_per_point;
+        self.pointer_pos = pos2(x as f32 * scale.x, y as f32 * scale.y);
+        self.input
+            .events
+            .push(egui::Event::PointerMoved(self.pointer_pos))
+    }
+
+    fn translate_key(&self, key: glfw::Key, scancode: glfw::Scancode) -> Option<egui::Key> {
+        if self.layout_aware_keys {
+            translate_key_with_layout(key, scancode)
+        } else {
+            translate_virtual_key_code(key)
+        }
+    }
+
+    /// Releases every key and mouse button egui believes is still held,
+    /// since their release events won't reach an unfocused window.
+    fn release_all(&mut self) {
+        self.set_modifiers(Modifiers::default());
+
+        let mut keys: Vec<_> = self.pressed_keys.drain().collect();
+        keys.sort();
+        for key in keys {
+            self.input.events.push(Event::Key {
+                key,
+                pressed: false,
+                modifiers: self.modifiers,
+            });
+        }
+
+        for button in std::mem::take(&mut self.pressed_buttons) {
+            self.input.events.push(Event::PointerButton {
+                pos: self.pointer_pos,
+                button,
+                pressed: false,
+                modifiers: self.modifiers,
+            });
         }
     }
+
+    /// Updates the tracked modifiers and the ones egui reads from `RawInput`.
+    fn set_modifiers(&mut self, modifiers: Modifiers) {
+        self.modifiers = modifiers;
+        self.input.modifiers = modifiers;
+    }
+}
+
+/// Passes `event` to egui like [`handle_event`], and returns whether egui consumed it.
+///
+/// Events that return `false` should also be handled by the application, e.g. clicks
+/// that aren't over any egui window. Call [`EguiInputState::update_wants`] every frame.
+pub fn route_event(event: glfw::WindowEvent, state: &mut EguiInputState) -> bool {
+    let consumed = state.consumes(&event);
+    handle_event(event, state);
+    consumed
 }
 
 pub fn handle_event(event: glfw::WindowEvent, state: &mut EguiInputState) {
@@ -43,127 +508,386 @@ pub fn handle_event(event: glfw::WindowEvent, state: &mut EguiInputState) {
 
     match event {
         FramebufferSize(width, height) => {
-            state.input.screen_rect = Some(Rect::from_min_size(
-                Pos2::new(0f32, 0f32),
-                egui::vec2(width as f32, height as f32)
-                    / state.input.pixels_per_point.unwrap_or(1.0),
-            ));
+            state.framebuffer_size = (width.max(0) as u32, height.max(0) as u32);
+            state.update_screen_rect();
         }
 
-        MouseButton(mouse_btn, glfw::Action::Press, _) => {
-            state.input.events.push(egui::Event::PointerButton {
-                pos: state.pointer_pos,
-                button: match mouse_btn {
-                    glfw::MouseButtonLeft => egui::PointerButton::Primary,
-                    glfw::MouseButtonRight => egui::PointerButton::Secondary,
-                    glfw::MouseButtonMiddle => egui::PointerButton::Middle,
-                    _ => unreachable!(),
-                },
-                pressed: true,
-                modifiers: state.modifiers,
-            })
+        Size(width, height) => {
+            state.window_size = (width.max(0) as u32, height.max(0) as u32);
         }
 
-        MouseButton(mouse_btn, glfw::Action::Release, _) => {
-            state.input.events.push(egui::Event::PointerButton {
-                pos: state.pointer_pos,
-                button: match mouse_btn {
-                    glfw::MouseButtonLeft => egui::PointerButton::Primary,
-                    glfw::MouseButtonRight => egui::PointerButton::Secondary,
-                    glfw::MouseButtonMiddle => egui::PointerButton::Middle,
-                    _ => unreachable!(),
-                },
-                pressed: false,
-                modifiers: state.modifiers,
-            })
+        Pos(x, y) => {
+            state.window_pos = (x, y);
+            state.update_refresh_rate();
         }
 
-        CursorPos(x, y) => {
-            state.pointer_pos = pos2(
-                x as f32 / state.input.pixels_per_point.unwrap_or(1.0),
-                y as f32 / state.input.pixels_per_point.unwrap_or(1.0),
-            );
-            state
-                .input
-                .events
-                .push(egui::Event::PointerMoved(state.pointer_pos))
+        // The content scale changes when the window moves to another monitor.
+        ContentScale(x_scale, _y_scale) => {
+            state.pixels_per_point = x_scale;
+            state.update_screen_rect();
+            state.update_refresh_rate();
         }
 
-        Key(keycode, _scancode, glfw::Action::Release, keymod) => {
-            use glfw::Modifiers as Mod;
-            if let Some(key) = translate_virtual_key_code(keycode) {
-                state.modifiers = Modifiers {
-                    alt: (keymod & Mod::Alt == Mod::Alt),
-                    ctrl: (keymod & Mod::Control == Mod::Control),
-                    shift: (keymod & Mod::Shift == Mod::Shift),
+        MouseButton(mouse_btn, glfw::Action::Press, keymod) => {
+            state.set_modifiers(translate_modifiers_with_command(
+                keymod,
+                state.command_modifier,
+            ));
 
-                    // TODO: GLFW doesn't seem to support the mac command key
-                    //       mac_cmd: keymod & Mod::LGUIMOD == Mod::LGUIMOD,
-                    command: (keymod & Mod::Control == Mod::Control),
+            if let Some(buttons) = state.navigation_buttons {
+                if mouse_btn == buttons.back {
+                    state.navigation_events.push(NavigationEvent::Back);
+                } else if mouse_btn == buttons.forward {
+                    state.navigation_events.push(NavigationEvent::Forward);
+                }
+            }
 
-                    ..Default::default()
-                };
+            if let Some(button) = translate_mouse_button(mouse_btn) {
+                if !state.pressed_buttons.contains(&button) {
+                    state.pressed_buttons.push(button);
+                }
 
-                state.input.events.push(Event::Key {
-                    key,
+                state.input.events.push(egui::Event::PointerButton {
+                    pos: state.pointer_pos,
+                    button,
+                    pressed: true,
+                    modifiers: state.modifiers,
+                })
+            }
+        }
+
+        MouseButton(mouse_btn, glfw::Action::Release, keymod) => {
+            state.set_modifiers(translate_modifiers_with_command(
+                keymod,
+                state.command_modifier,
+            ));
+
+            if let Some(button) = translate_mouse_button(mouse_btn) {
+                state.pressed_buttons.retain(|&b| b != button);
+
+                state.input.events.push(egui::Event::PointerButton {
+                    pos: state.pointer_pos,
+                    button,
                     pressed: false,
                     modifiers: state.modifiers,
-                });
+                })
             }
         }
 
-        Key(keycode, _scancode, glfw::Action::Press | glfw::Action::Repeat, keymod) => {
-            use glfw::Modifiers as Mod;
-            if let Some(key) = translate_virtual_key_code(keycode) {
-                state.modifiers = Modifiers {
-                    alt: (keymod & Mod::Alt == Mod::Alt),
-                    ctrl: (keymod & Mod::Control == Mod::Control),
-                    shift: (keymod & Mod::Shift == Mod::Shift),
-
-                    // TODO: GLFW doesn't seem to support the mac command key
-                    //       mac_cmd: keymod & Mod::LGUIMOD == Mod::LGUIMOD,
-                    command: (keymod & Mod::Control == Mod::Control),
-
-                    ..Default::default()
-                };
-
-                if state.modifiers.command && key == egui::Key::X {
-                    state.input.events.push(egui::Event::Cut);
-                } else if state.modifiers.command && key == egui::Key::C {
-                    state.input.events.push(egui::Event::Copy);
-                } else if state.modifiers.command && key == egui::Key::V {
-                    if let Some(clipboard_ctx) = state.clipboard.as_mut() {
-                        state.input.events.push(egui::Event::Text(
-                            clipboard_ctx
-                                .get_contents()
-                                .unwrap_or_else(|_| "".to_string()),
-                        ));
-                    }
-                } else {
+        Focus(focused) => {
+            state.input.has_focus = focused;
+            if !focused {
+                state.release_all();
+            }
+        }
+
+        CursorPos(x, y) => state.set_pointer_pos(x, y),
+
+        // The position isn't part of the enter event, see `EguiInputState::sync_pointer_pos`.
+        CursorEnter(false) => state.input.events.push(egui::Event::PointerGone),
+
+        Key(keycode, scancode, glfw::Action::Release, keymod) => {
+            state.set_modifiers(modifiers_after_key_event(
+                keycode,
+                glfw::Action::Release,
+                keymod,
+                state.command_modifier,
+            ));
+
+            // Keys whose press triggered a clipboard shortcut were never pressed as far as egui knows.
+            if let Some(key) = state.translate_key(keycode, scancode) {
+                if state.pressed_keys.remove(&key) {
                     state.input.events.push(Event::Key {
                         key,
-                        pressed: true,
+                        pressed: false,
                         modifiers: state.modifiers,
                     });
                 }
             }
         }
 
+        Key(keycode, scancode, action @ (glfw::Action::Press | glfw::Action::Repeat), keymod) => {
+            state.set_modifiers(modifiers_after_key_event(
+                keycode,
+                action,
+                keymod,
+                state.command_modifier,
+            ));
+
+            if let Some(key) = state.translate_key(keycode, scancode) {
+                let action = state
+                    .clipboard_shortcuts
+                    .iter()
+                    .find(|shortcut| {
+                        shortcut.key == key && state.modifiers.matches(shortcut.modifiers)
+                    })
+                    .map(|shortcut| shortcut.action);
+
+                match action {
+                    Some(ClipboardAction::Cut) => state.input.events.push(Event::Cut),
+                    Some(ClipboardAction::Copy) => state.input.events.push(Event::Copy),
+                    Some(ClipboardAction::Paste) => {
+                        if let Some(clipboard_ctx) = state.clipboard.as_mut() {
+                            if let Ok(contents) = clipboard_ctx.get_contents() {
+                                let contents = str::replace(&contents, "\r\n", "\n");
+                                if !contents.is_empty() {
+                                    state.input.events.push(Event::Paste(contents));
+                                }
+                            }
+                        }
+                    }
+                    // Only forward keys that aren't shortcuts, or egui would act on both,
+                    // e.g. cut the selection on Shift+Delete and then delete a character.
+                    None => {
+                        state.pressed_keys.insert(key);
+
+                        state.input.events.push(Event::Key {
+                            key,
+                            pressed: true,
+                            modifiers: state.modifiers,
+                        });
+                    }
+                }
+            }
+        }
+
+        FileDrop(paths) => {
+            for path in paths {
+                let dropped_file = dropped_file(path, state.read_dropped_files);
+                state.input.dropped_files.push(dropped_file);
+            }
+        }
+
         Char(c) => {
             state.input.events.push(Event::Text(c.to_string()));
         }
 
         Scroll(x, y) => {
-            state
-                .input
-                .events
-                .push(Event::Scroll(vec2(x as f32, y as f32)));
+            let settings = state.scroll;
+            let mut delta = settings.scroll_delta(x, y);
+
+            if settings.command_zooms && (state.modifiers.ctrl || state.modifiers.command) {
+                let zoom = (delta.y / settings.zoom_points).exp();
+                state.input.events.push(Event::Zoom(zoom));
+            } else {
+                if settings.shift_scrolls_horizontally && state.modifiers.shift && delta.x == 0.0 {
+                    delta = vec2(delta.y, 0.0);
+                }
+
+                state.input.events.push(Event::Scroll(delta));
+            }
         }
 
         _ => {}
     }
 }
 
+/// Refresh rate of the monitor showing most of a window at `pos` of `size`, in screen
+/// coordinates. This also finds the monitor of a fullscreen window.
+fn monitor_refresh_rate(pos: (i32, i32), size: (u32, u32)) -> Option<u32> {
+    let mut count = 0;
+    let monitors = unsafe { glfw::ffi::glfwGetMonitors(&mut count) };
+    if monitors.is_null() {
+        return None;
+    }
+    let monitors = unsafe { std::slice::from_raw_parts(monitors, count.max(0) as usize) };
+
+    let (right, bottom) = (pos.0 + size.0 as i32, pos.1 + size.1 as i32);
+
+    let mut best: Option<(i64, i32)> = None;
+    for &monitor in monitors {
+        let mode = match unsafe { glfw::ffi::glfwGetVideoMode(monitor).as_ref() } {
+            Some(mode) => mode,
+            None => continue,
+        };
+
+        let (mut x, mut y) = (0, 0);
+        unsafe {
+            glfw::ffi::glfwGetMonitorPos(monitor, &mut x, &mut y);
+        }
+
+        let overlap_width = right.min(x + mode.width) - pos.0.max(x);
+        let overlap_height = bottom.min(y + mode.height) - pos.1.max(y);
+        let overlap = overlap_width.max(0) as i64 * overlap_height.max(0) as i64;
+
+        // The primary monitor comes first, and wins when the window is on no monitor.
+        match best {
+            Some((best_overlap, _)) if overlap <= best_overlap => {}
+            _ => best = Some((overlap, mode.refreshRate)),
+        }
+    }
+
+    best.map(|(_, refresh_rate)| refresh_rate)
+        .filter(|&refresh_rate| refresh_rate > 0)
+        .map(|refresh_rate| refresh_rate as u32)
+}
+
+/// Translates modifiers with the platform's default [`CommandModifier`].
+pub fn translate_modifiers(keymod: glfw::Modifiers) -> egui::Modifiers {
+    translate_modifiers_with_command(keymod, CommandModifier::default())
+}
+
+pub fn translate_modifiers_with_command(
+    keymod: glfw::Modifiers,
+    command_modifier: CommandModifier,
+) -> egui::Modifiers {
+    use glfw::Modifiers as Mod;
+
+    let ctrl = keymod & Mod::Control == Mod::Control;
+    let super_key = keymod & Mod::Super == Mod::Super;
+
+    let (command, mac_cmd) = match command_modifier {
+        CommandModifier::Ctrl => (ctrl, false),
+        CommandModifier::Super => (super_key, super_key),
+        CommandModifier::Either => (ctrl || super_key, super_key),
+    };
+
+    Modifiers {
+        alt: (keymod & Mod::Alt == Mod::Alt),
+        ctrl,
+        shift: (keymod & Mod::Shift == Mod::Shift),
+        mac_cmd,
+        command,
+    }
+}
+
+/// Modifier state after a key event.
+///
+/// Depending on the platform, GLFW reports the modifiers of a modifier key event
+/// from before or after the key changed state, so the key itself is applied on top.
+fn modifiers_after_key_event(
+    keycode: glfw::Key,
+    action: glfw::Action,
+    mut keymod: glfw::Modifiers,
+    command_modifier: CommandModifier,
+) -> egui::Modifiers {
+    use glfw::Key::*;
+    use glfw::Modifiers as Mod;
+
+    let pressed = action != glfw::Action::Release;
+
+    match keycode {
+        LeftShift | RightShift => keymod.set(Mod::Shift, pressed),
+        LeftAlt | RightAlt => keymod.set(Mod::Alt, pressed),
+        LeftControl | RightControl => keymod.set(Mod::Control, pressed),
+        LeftSuper | RightSuper => keymod.set(Mod::Super, pressed),
+        _ => {}
+    }
+
+    translate_modifiers_with_command(keymod, command_modifier)
+}
+
+fn dropped_file(path: std::path::PathBuf, read_bytes: bool) -> egui::DroppedFile {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let last_modified = std::fs::metadata(&path)
+        .and_then(|metadata| metadata.modified())
+        .ok();
+
+    let bytes = if read_bytes {
+        match std::fs::read(&path) {
+            Ok(bytes) => Some(bytes.into()),
+            Err(err) => {
+                eprintln!("Failed to read dropped file {}: {}", path.display(), err);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    egui::DroppedFile {
+        path: Some(path),
+        name,
+        last_modified,
+        bytes,
+    }
+}
+
+/// Buttons 4 and 5 are usually the back and forward thumb buttons.
+/// egui has no equivalent for buttons 6 to 8.
+pub fn translate_mouse_button(button: glfw::MouseButton) -> Option<egui::PointerButton> {
+    use glfw::MouseButton::*;
+
+    Some(match button {
+        Button1 => PointerButton::Primary,
+        Button2 => PointerButton::Secondary,
+        Button3 => PointerButton::Middle,
+        Button4 => PointerButton::Extra1,
+        Button5 => PointerButton::Extra2,
+
+        _ => {
+            return None;
+        }
+    })
+}
+
+/// Like [`translate_virtual_key_code`], but letter keys are mapped by the character they
+/// produce in the current keyboard layout, so e.g. Ctrl+Z on AZERTY is reported as `Key::Z`.
+///
+/// Keys without a printable name, and layouts without Latin letters, fall back to the
+/// positional mapping. Letter positions that produce punctuation in this layout are ignored.
+pub fn translate_key_with_layout(key: glfw::Key, scancode: glfw::Scancode) -> Option<egui::Key> {
+    let name = glfw::get_key_name(Some(key), Some(scancode));
+
+    let mut chars = name.as_deref().unwrap_or_default().chars();
+    let c = match (chars.next(), chars.next()) {
+        (Some(c), None) => c,
+        _ => return translate_virtual_key_code(key),
+    };
+
+    let positional = translate_virtual_key_code(key);
+    if c.is_ascii_alphabetic() {
+        translate_letter(c)
+    } else if c.is_ascii() && positional.is_some_and(is_letter) {
+        None
+    } else {
+        positional
+    }
+}
+
+fn translate_letter(c: char) -> Option<egui::Key> {
+    Some(match c.to_ascii_uppercase() {
+        'A' => Key::A,
+        'B' => Key::B,
+        'C' => Key::C,
+        'D' => Key::D,
+        'E' => Key::E,
+        'F' => Key::F,
+        'G' => Key::G,
+        'H' => Key::H,
+        'I' => Key::I,
+        'J' => Key::J,
+        'K' => Key::K,
+        'L' => Key::L,
+        'M' => Key::M,
+        'N' => Key::N,
+        'O' => Key::O,
+        'P' => Key::P,
+        'Q' => Key::Q,
+        'R' => Key::R,
+        'S' => Key::S,
+        'T' => Key::T,
+        'U' => Key::U,
+        'V' => Key::V,
+        'W' => Key::W,
+        'X' => Key::X,
+        'Y' => Key::Y,
+        'Z' => Key::Z,
+
+        _ => {
+            return None;
+        }
+    })
+}
+
+fn is_letter(key: egui::Key) -> bool {
+    (Key::A..=Key::Z).contains(&key)
+}
+
 pub fn translate_virtual_key_code(key: glfw::Key) -> Option<egui::Key> {
     use glfw::Key::*;
 
@@ -178,7 +902,7 @@ pub fn translate_virtual_key_code(key: glfw::Key) -> Option<egui::Key> {
         Backspace => Key::Backspace,
         Space => Key::Space,
 
-        Enter => Key::Enter,
+        Enter | KpEnter => Key::Enter,
 
         Insert => Key::Insert,
         Home => Key::Home,
@@ -214,33 +938,75 @@ pub fn translate_virtual_key_code(key: glfw::Key) -> Option<egui::Key> {
         Y => Key::Y,
         Z => Key::Z,
 
+        Num0 | Kp0 => Key::Num0,
+        Num1 | Kp1 => Key::Num1,
+        Num2 | Kp2 => Key::Num2,
+        Num3 | Kp3 => Key::Num3,
+        Num4 | Kp4 => Key::Num4,
+        Num5 | Kp5 => Key::Num5,
+        Num6 | Kp6 => Key::Num6,
+        Num7 | Kp7 => Key::Num7,
+        Num8 | Kp8 => Key::Num8,
+        Num9 | Kp9 => Key::Num9,
+
+        F1 => Key::F1,
+        F2 => Key::F2,
+        F3 => Key::F3,
+        F4 => Key::F4,
+        F5 => Key::F5,
+        F6 => Key::F6,
+        F7 => Key::F7,
+        F8 => Key::F8,
+        F9 => Key::F9,
+        F10 => Key::F10,
+        F11 => Key::F11,
+        F12 => Key::F12,
+        F13 => Key::F13,
+        F14 => Key::F14,
+        F15 => Key::F15,
+        F16 => Key::F16,
+        F17 => Key::F17,
+        F18 => Key::F18,
+        F19 => Key::F19,
+        F20 => Key::F20,
+
         _ => {
             return None;
         }
     })
 }
 
+/// GLFW's standard cursor closest to `cursor_icon`, see [`standard_cursor`].
+///
+/// Grabbing shows the hand, other icons without a standard cursor show the arrow.
+/// [`CursorManager`] draws proper cursors for those.
 pub fn translate_cursor(cursor_icon: egui::CursorIcon) -> glfw::StandardCursor {
     match cursor_icon {
-        CursorIcon::Default => glfw::StandardCursor::Arrow,
-
-        CursorIcon::PointingHand => glfw::StandardCursor::Hand,
-
-        CursorIcon::ResizeHorizontal => glfw::StandardCursor::HResize,
-        CursorIcon::ResizeVertical => glfw::StandardCursor::VResize,
-        // TODO: GLFW doesnt have these specific resize cursors, so we'll just use the HResize and VResize ones instead
-        CursorIcon::ResizeNeSw => glfw::StandardCursor::HResize,
-        CursorIcon::ResizeNwSe => glfw::StandardCursor::VResize,
+        CursorIcon::Grab | CursorIcon::Grabbing => glfw::StandardCursor::Hand,
+        _ => standard_cursor(cursor_icon).unwrap_or(glfw::StandardCursor::Arrow),
+    }
+}
 
-        CursorIcon::Text => glfw::StandardCursor::IBeam,
-        CursorIcon::Crosshair => glfw::StandardCursor::Crosshair,
+/// GLFW's standard cursor for `cursor_icon`, if it has one of the same shape.
+///
+/// [`CursorManager`] draws cursors for the icons that don't.
+pub fn standard_cursor(cursor_icon: egui::CursorIcon) -> Option<glfw::StandardCursor> {
+    match cursor_icon {
+        CursorIcon::Default => Some(glfw::StandardCursor::Arrow),
+        CursorIcon::PointingHand => Some(glfw::StandardCursor::Hand),
+        CursorIcon::Text => Some(glfw::StandardCursor::IBeam),
+        CursorIcon::Crosshair => Some(glfw::StandardCursor::Crosshair),
 
-        CursorIcon::Grab | CursorIcon::Grabbing => glfw::StandardCursor::Hand,
+        CursorIcon::ResizeHorizontal
+        | CursorIcon::ResizeEast
+        | CursorIcon::ResizeWest
+        | CursorIcon::ResizeColumn => Some(glfw::StandardCursor::HResize),
+        CursorIcon::ResizeVertical
+        | CursorIcon::ResizeNorth
+        | CursorIcon::ResizeSouth
+        | CursorIcon::ResizeRow => Some(glfw::StandardCursor::VResize),
 
-        // TODO: Same for these
-        CursorIcon::NotAllowed | CursorIcon::NoDrop => glfw::StandardCursor::Arrow,
-        CursorIcon::Wait => glfw::StandardCursor::Arrow,
-        _ => glfw::StandardCursor::Arrow,
+        _ => None,
     }
 }
 
@@ -262,3 +1028,161 @@ pub fn copy_to_clipboard(egui_state: &mut EguiInputState, copy_text: String) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translates_every_representable_key() {
+        use glfw::Key as G;
+
+        let table = [
+            (G::Left, Key::ArrowLeft),
+            (G::Up, Key::ArrowUp),
+            (G::Right, Key::ArrowRight),
+            (G::Down, Key::ArrowDown),
+            (G::Escape, Key::Escape),
+            (G::Tab, Key::Tab),
+            (G::Backspace, Key::Backspace),
+            (G::Space, Key::Space),
+            (G::Enter, Key::Enter),
+            (G::KpEnter, Key::Enter),
+            (G::Insert, Key::Insert),
+            (G::Home, Key::Home),
+            (G::Delete, Key::Delete),
+            (G::End, Key::End),
+            (G::PageDown, Key::PageDown),
+            (G::PageUp, Key::PageUp),
+            (G::A, Key::A),
+            (G::B, Key::B),
+            (G::C, Key::C),
+            (G::D, Key::D),
+            (G::E, Key::E),
+            (G::F, Key::F),
+            (G::G, Key::G),
+            (G::H, Key::H),
+            (G::I, Key::I),
+            (G::J, Key::J),
+            (G::K, Key::K),
+            (G::L, Key::L),
+            (G::M, Key::M),
+            (G::N, Key::N),
+            (G::O, Key::O),
+            (G::P, Key::P),
+            (G::Q, Key::Q),
+            (G::R, Key::R),
+            (G::S, Key::S),
+            (G::T, Key::T),
+            (G::U, Key::U),
+            (G::V, Key::V),
+            (G::W, Key::W),
+            (G::X, Key::X),
+            (G::Y, Key::Y),
+            (G::Z, Key::Z),
+            (G::Num0, Key::Num0),
+            (G::Num1, Key::Num1),
+            (G::Num2, Key::Num2),
+            (G::Num3, Key::Num3),
+            (G::Num4, Key::Num4),
+            (G::Num5, Key::Num5),
+            (G::Num6, Key::Num6),
+            (G::Num7, Key::Num7),
+            (G::Num8, Key::Num8),
+            (G::Num9, Key::Num9),
+            (G::Kp0, Key::Num0),
+            (G::Kp1, Key::Num1),
+            (G::Kp2, Key::Num2),
+            (G::Kp3, Key::Num3),
+            (G::Kp4, Key::Num4),
+            (G::Kp5, Key::Num5),
+            (G::Kp6, Key::Num6),
+            (G::Kp7, Key::Num7),
+            (G::Kp8, Key::Num8),
+            (G::Kp9, Key::Num9),
+            (G::F1, Key::F1),
+            (G::F2, Key::F2),
+            (G::F3, Key::F3),
+            (G::F4, Key::F4),
+            (G::F5, Key::F5),
+            (G::F6, Key::F6),
+            (G::F7, Key::F7),
+            (G::F8, Key::F8),
+            (G::F9, Key::F9),
+            (G::F10, Key::F10),
+            (G::F11, Key::F11),
+            (G::F12, Key::F12),
+            (G::F13, Key::F13),
+            (G::F14, Key::F14),
+            (G::F15, Key::F15),
+            (G::F16, Key::F16),
+            (G::F17, Key::F17),
+            (G::F18, Key::F18),
+            (G::F19, Key::F19),
+            (G::F20, Key::F20),
+        ];
+
+        for (glfw_key, egui_key) in table {
+            assert_eq!(
+                translate_virtual_key_code(glfw_key),
+                Some(egui_key),
+                "{:?}",
+                glfw_key
+            );
+        }
+    }
+
+    #[test]
+    fn clipboard_shortcuts_are_not_forwarded_as_keys() {
+        let mut state = EguiInputState::new(RawInput::default());
+
+        let shift = glfw::Modifiers::Shift;
+        handle_event(
+            glfw::WindowEvent::Key(glfw::Key::Delete, 0, glfw::Action::Press, shift),
+            &mut state,
+        );
+        handle_event(
+            glfw::WindowEvent::Key(glfw::Key::Delete, 0, glfw::Action::Release, shift),
+            &mut state,
+        );
+
+        let events = &state.input.events;
+        assert_eq!(events.iter().filter(|e| **e == Event::Cut).count(), 1);
+        assert!(!events.iter().any(|e| matches!(
+            e,
+            Event::Key {
+                key: Key::Delete,
+                ..
+            }
+        )));
+    }
+
+    #[test]
+    fn manual_clock_reports_exact_times() {
+        let mut state = EguiInputState::new(RawInput::default());
+        state.clock = FrameClock::Manual;
+
+        state.advance_time(0.25);
+        let input = state.take();
+        assert_eq!(input.time, Some(0.25));
+        assert_eq!(input.predicted_dt, 0.25);
+
+        state.advance_time(0.5);
+        let input = state.take();
+        assert_eq!(input.time, Some(0.75));
+        assert_eq!(input.predicted_dt, 0.5);
+
+        let input = state.take();
+        assert_eq!(input.time, Some(0.75));
+        assert_eq!(input.predicted_dt, 0.5);
+    }
+
+    #[test]
+    fn ignores_keys_egui_cannot_represent() {
+        use glfw::Key as G;
+
+        for glfw_key in [G::F21, G::F25, G::LeftShift, G::CapsLock, G::KpDecimal] {
+            assert_eq!(translate_virtual_key_code(glfw_key), None, "{:?}", glfw_key);
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! Opening the URLs egui asks for, e.g. when a `ui.hyperlink` is clicked.
+
+use egui::output::OpenUrl;
+use std::io;
+use std::process::Command;
+
+/// Decides what happens to a URL before it is opened, see [`UrlOpener::filter`].
+pub type UrlFilter = Box<dyn FnMut(OpenUrl) -> Option<OpenUrl>>;
+
+/// Opens a URL, see [`UrlOpener::open`].
+pub type OpenUrlFn = Box<dyn FnMut(&OpenUrl) -> io::Result<()>>;
+
+/// Opens `egui::PlatformOutput::open_url`.
+pub struct UrlOpener {
+    /// Called with every URL before it is opened. Return `None` to veto the URL, for
+    /// example after handling it in the application, or a different URL to open instead.
+    pub filter: Option<UrlFilter>,
+
+    /// Opens the URLs that pass the filter, [`open_with_platform_opener`] by default.
+    /// Tests can replace it with a stub that records URLs instead of launching a browser.
+    pub open: OpenUrlFn,
+}
+
+impl Default for UrlOpener {
+    fn default() -> Self {
+        Self {
+            filter: None,
+            open: Box::new(open_with_platform_opener),
+        }
+    }
+}
+
+impl UrlOpener {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Opens the URL egui asked for this frame, if any.
+    pub fn handle(&mut self, open_url: Option<OpenUrl>) {
+        let open_url = match self.filter.as_mut() {
+            Some(filter) => open_url.and_then(filter),
+            None => open_url,
+        };
+
+        if let Some(open_url) = open_url {
+            if let Err(err) = (self.open)(&open_url) {
+                eprintln!("Failed to open {}: {}", open_url.url, err);
+            }
+        }
+    }
+}
+
+/// Schemes [`open_with_platform_opener`] will open.
+const OPENABLE_SCHEMES: [&str; 3] = ["http", "https", "mailto"];
+
+/// Opens `open_url` with the platform's opener: `xdg-open`, `open` on macOS, or the
+/// URL protocol handler on Windows.
+///
+/// These hand the URL to the default browser, which decides between a tab and a window
+/// whatever `new_tab` says, as an application outside the browser has no tab to reuse.
+///
+/// Only `http`, `https` and `mailto` URLs are opened, as the openers would just as well
+/// run a local program for a `file:` URL or a path. Other URLs fail with
+/// `io::ErrorKind::InvalidInput`, [`UrlOpener::filter`] or [`UrlOpener::open`] can
+/// handle them instead.
+pub fn open_with_platform_opener(open_url: &OpenUrl) -> io::Result<()> {
+    let url = open_url.url.as_str();
+
+    // Checking the scheme also rules out a leading dash, which openers would take as an option.
+    let scheme = url.split(':').next().unwrap_or_default();
+    if scheme.len() == url.len()
+        || !OPENABLE_SCHEMES
+            .iter()
+            .any(|openable| scheme.eq_ignore_ascii_case(openable))
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "not an http, https or mailto URL",
+        ));
+    }
+
+    let mut command = if cfg!(target_os = "windows") {
+        let mut command = Command::new("rundll32");
+        command.arg("url.dll,FileProtocolHandler");
+        command
+    } else if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else {
+        Command::new("xdg-open")
+    };
+
+    let mut child = command.arg(url).spawn()?;
+
+    // Reap the opener once it exits, so it doesn't linger as a zombie process.
+    std::thread::spawn(move || child.wait());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn recording_opener() -> (UrlOpener, Rc<RefCell<Vec<OpenUrl>>>) {
+        let opened = Rc::new(RefCell::new(Vec::new()));
+        let recorded = opened.clone();
+
+        let opener = UrlOpener {
+            filter: None,
+            open: Box::new(move |open_url| {
+                recorded.borrow_mut().push(open_url.clone());
+                Ok(())
+            }),
+        };
+        (opener, opened)
+    }
+
+    #[test]
+    fn opens_urls_as_requested() {
+        let (mut opener, opened) = recording_opener();
+
+        opener.handle(None);
+        opener.handle(Some(OpenUrl::new_tab("https://github.com/emilk/egui")));
+        opener.handle(Some(OpenUrl::same_tab("https://docs.rs/egui")));
+
+        let opened = opened.borrow();
+        assert_eq!(opened.len(), 2);
+        assert_eq!(opened[0].url, "https://github.com/emilk/egui");
+        assert!(opened[0].new_tab);
+        assert_eq!(opened[1].url, "https://docs.rs/egui");
+        assert!(!opened[1].new_tab);
+    }
+
+    #[test]
+    fn filter_can_veto_and_reroute() {
+        let (mut opener, opened) = recording_opener();
+        opener.filter = Some(Box::new(|open_url: OpenUrl| {
+            if open_url.url.starts_with("app://") {
+                None
+            } else {
+                Some(OpenUrl::same_tab(
+                    open_url.url.replace("http://", "https://"),
+                ))
+            }
+        }));
+
+        opener.handle(Some(OpenUrl::new_tab("app://settings")));
+        opener.handle(Some(OpenUrl::new_tab("http://example.com")));
+
+        let opened = opened.borrow();
+        assert_eq!(opened.len(), 1);
+        assert_eq!(opened[0].url, "https://example.com");
+        assert!(!opened[0].new_tab);
+    }
+
+    #[test]
+    fn platform_opener_rejects_options() {
+        let err = open_with_platform_opener(&OpenUrl::new_tab("--help")).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn platform_opener_rejects_files_and_paths() {
+        for url in [
+            "file:///C:/Windows/System32/calc.exe",
+            "FILE:///usr/bin/xterm",
+            "C:\\Windows\\System32\\calc.exe",
+            "/usr/bin/xterm",
+            "ms-settings:",
+            "",
+        ] {
+            let err = open_with_platform_opener(&OpenUrl::new_tab(url)).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{}", url);
+        }
+    }
+}
@@ -1,18 +1,21 @@
 extern crate gl;
 
 use egui::{
-    emath::Rect,
+    emath::{Pos2, Rect, Vec2},
     epaint::{Mesh, Primitive},
     Color32, TextureFilter,
 };
 
 use gl::types::{GLchar, GLenum, GLint, GLsizeiptr, GLuint};
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::ffi::{c_void, CString};
+use std::rc::Rc;
 
-fn compile_shader(src: &str, ty: GLenum) -> GLuint {
-    let shader = unsafe { gl::CreateShader(ty) };
+fn compile_shader(src: &str, ty: GLenum) -> Result<GLuint, String> {
+    let c_str = CString::new(src.as_bytes()).map_err(|err| err.to_string())?;
 
-    let c_str = CString::new(src.as_bytes()).unwrap();
+    let shader = unsafe { gl::CreateShader(ty) };
     unsafe {
         gl::ShaderSource(shader, 1, &c_str.as_ptr(), core::ptr::null());
         gl::CompileShader(shader);
@@ -38,18 +41,16 @@ fn compile_shader(src: &str, ty: GLenum) -> GLuint {
                 core::ptr::null_mut(),
                 buf.as_mut_ptr() as *mut GLchar,
             );
+            gl::DeleteShader(shader);
         }
 
-        panic!(
-            "{}",
-            core::str::from_utf8(&buf).expect("ShaderInfoLog not valid utf8")
-        );
+        return Err(String::from_utf8_lossy(&buf).into_owned());
     }
 
-    shader
+    Ok(shader)
 }
 
-fn link_program(vs: GLuint, fs: GLuint) -> GLuint {
+fn link_program(vs: GLuint, fs: GLuint) -> Result<GLuint, String> {
     let program = unsafe { gl::CreateProgram() };
 
     unsafe {
@@ -78,21 +79,383 @@ fn link_program(vs: GLuint, fs: GLuint) -> GLuint {
                 core::ptr::null_mut(),
                 buf.as_mut_ptr() as *mut GLchar,
             );
+            gl::DeleteProgram(program);
         }
 
-        panic!(
-            "{}",
-            core::str::from_utf8(&buf).expect("ProgramInfoLog not valid utf8")
-        );
+        return Err(String::from_utf8_lossy(&buf).into_owned());
+    }
+
+    Ok(program)
+}
+
+/// Line of `fragment.frag` that is replaced with the post-process snippet.
+const POST_PROCESS_MARKER: &str = "// @post_process";
+
+/// Builds the egui program with `post_process` spliced in, returning the info log
+/// of the failing step on error.
+fn build_program(post_process: &str) -> Result<GLuint, String> {
+    let fragment_src =
+        include_str!("shader/fragment.frag").replace(POST_PROCESS_MARKER, post_process);
+
+    let vs = compile_shader(include_str!("shader/vertex.vert"), gl::VERTEX_SHADER)?;
+    let fs = match compile_shader(&fragment_src, gl::FRAGMENT_SHADER) {
+        Ok(fs) => fs,
+        Err(err) => {
+            unsafe {
+                gl::DeleteShader(vs);
+            }
+            return Err(err);
+        }
+    };
+
+    let program = link_program(vs, fs);
+
+    unsafe {
+        gl::DeleteShader(vs);
+        gl::DeleteShader(fs);
     }
 
     program
 }
 
+/// Value of a uniform declared by a post-process shader, see [`Painter::set_uniform`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Uniform {
+    Int(i32),
+    Float(f32),
+    Vec2([f32; 2]),
+    Vec3([f32; 3]),
+    Vec4([f32; 4]),
+}
+
+impl Uniform {
+    fn apply(&self, location: GLint) {
+        unsafe {
+            match *self {
+                Uniform::Int(v) => gl::Uniform1i(location, v),
+                Uniform::Float(v) => gl::Uniform1f(location, v),
+                Uniform::Vec2([x, y]) => gl::Uniform2f(location, x, y),
+                Uniform::Vec3([x, y, z]) => gl::Uniform3f(location, x, y, z),
+                Uniform::Vec4([x, y, z, w]) => gl::Uniform4f(location, x, y, z, w),
+            }
+        }
+    }
+}
+
+/// Affine 2D transform applied to everything a [`Painter`] paints, in points.
+///
+/// A point `p` is mapped to `x_axis * p.x + y_axis * p.y + translation`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform2D {
+    pub x_axis: Vec2,
+    pub y_axis: Vec2,
+    pub translation: Vec2,
+}
+
+impl Default for Transform2D {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Transform2D {
+    pub const IDENTITY: Self = Self {
+        x_axis: Vec2::X,
+        y_axis: Vec2::Y,
+        translation: Vec2::ZERO,
+    };
+
+    pub fn from_translation(translation: Vec2) -> Self {
+        Self {
+            translation,
+            ..Self::IDENTITY
+        }
+    }
+
+    pub fn from_scale(scale: Vec2) -> Self {
+        Self {
+            x_axis: Vec2::new(scale.x, 0.0),
+            y_axis: Vec2::new(0.0, scale.y),
+            translation: Vec2::ZERO,
+        }
+    }
+
+    /// Clockwise rotation on screen, since the y axis points down.
+    pub fn from_rotation(radians: f32) -> Self {
+        let (sin, cos) = radians.sin_cos();
+        Self {
+            x_axis: Vec2::new(cos, sin),
+            y_axis: Vec2::new(-sin, cos),
+            translation: Vec2::ZERO,
+        }
+    }
+
+    /// Returns the transform that applies `self` first and then `next`.
+    pub fn then(&self, next: &Transform2D) -> Self {
+        Self {
+            x_axis: next.transform_vec(self.x_axis),
+            y_axis: next.transform_vec(self.y_axis),
+            translation: next.transform_pos(self.translation.to_pos2()).to_vec2(),
+        }
+    }
+
+    pub fn transform_vec(&self, v: Vec2) -> Vec2 {
+        self.x_axis * v.x + self.y_axis * v.y
+    }
+
+    pub fn transform_pos(&self, p: Pos2) -> Pos2 {
+        (self.transform_vec(p.to_vec2()) + self.translation).to_pos2()
+    }
+
+    /// The inverse transform, useful to map pointer positions back into UI space.
+    /// Returns `None` if the transform is degenerate.
+    pub fn inverse(&self) -> Option<Self> {
+        let det = self.x_axis.x * self.y_axis.y - self.y_axis.x * self.x_axis.y;
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+
+        let inverse = Self {
+            x_axis: Vec2::new(self.y_axis.y, -self.x_axis.y) / det,
+            y_axis: Vec2::new(-self.y_axis.x, self.x_axis.x) / det,
+            translation: Vec2::ZERO,
+        };
+
+        Some(Self {
+            translation: -inverse.transform_vec(self.translation),
+            ..inverse
+        })
+    }
+
+    /// Whether rectangles stay axis-aligned, i.e. the rotation is a multiple of 90°.
+    fn is_axis_aligned(&self) -> bool {
+        // Rotations by multiples of 90° leave tiny float errors, e.g. cos(π/2) ≈ -4e-8.
+        let epsilon = 1e-6 * (self.x_axis.length() + self.y_axis.length());
+        let is_zero = |v: f32| v.abs() <= epsilon;
+
+        (is_zero(self.x_axis.x) && is_zero(self.y_axis.y))
+            || (is_zero(self.x_axis.y) && is_zero(self.y_axis.x))
+    }
+
+    /// Bounding box of the transformed `rect`.
+    fn transform_rect(&self, rect: &Rect) -> Rect {
+        Rect::from_points(&[
+            self.transform_pos(rect.left_top()),
+            self.transform_pos(rect.right_top()),
+            self.transform_pos(rect.left_bottom()),
+            self.transform_pos(rect.right_bottom()),
+        ])
+    }
+
+    /// Column major 3x3 matrix, as expected by `glUniformMatrix3fv`.
+    fn to_mat3(self) -> [f32; 9] {
+        [
+            self.x_axis.x,
+            self.x_axis.y,
+            0.0,
+            self.y_axis.x,
+            self.y_axis.y,
+            0.0,
+            self.translation.x,
+            self.translation.y,
+            1.0,
+        ]
+    }
+}
+
+/// How the egui pass interacts with the depth buffer.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum DepthMode {
+    /// No depth testing, the UI is drawn on top of everything (default).
+    #[default]
+    Disabled,
+
+    /// The UI is drawn at `depth`, in normalized device coordinates (-1 near, 1 far),
+    /// and tested against the depth buffer with `GL_LEQUAL`.
+    Test {
+        depth: f32,
+
+        /// Also write `depth` wherever the UI is drawn.
+        write: bool,
+    },
+}
+
+/// How the egui pass interacts with the stencil buffer.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum StencilMode {
+    /// The stencil buffer is ignored (default).
+    #[default]
+    Disabled,
+
+    /// The UI is only drawn where `stencil & mask == reference & mask`.
+    Test { reference: u8, mask: u8 },
+
+    /// The UI is always drawn and writes `reference` into the stencil bits in `mask`
+    /// wherever it covers, e.g. to mask where a 3D overlay may draw afterwards.
+    Write { reference: u8, mask: u8 },
+}
+
+/// Depth and stencil state of the application, saved before painting and restored after,
+/// so the egui pass doesn't leak its [`DepthMode`] and [`StencilMode`].
+struct DepthStencilState {
+    depth_test: bool,
+    depth_func: GLint,
+    depth_mask: GLint,
+    stencil_test: bool,
+
+    /// Func, ref, value mask, fail, depth fail, depth pass and write mask of each face.
+    stencil_front: [GLint; 7],
+    stencil_back: [GLint; 7],
+}
+
+impl DepthStencilState {
+    fn save() -> Self {
+        fn get(name: GLenum) -> GLint {
+            let mut value = 0;
+            unsafe {
+                gl::GetIntegerv(name, &mut value);
+            }
+            value
+        }
+
+        unsafe {
+            DepthStencilState {
+                depth_test: gl::IsEnabled(gl::DEPTH_TEST) == gl::TRUE,
+                depth_func: get(gl::DEPTH_FUNC),
+                depth_mask: get(gl::DEPTH_WRITEMASK),
+                stencil_test: gl::IsEnabled(gl::STENCIL_TEST) == gl::TRUE,
+                stencil_front: [
+                    get(gl::STENCIL_FUNC),
+                    get(gl::STENCIL_REF),
+                    get(gl::STENCIL_VALUE_MASK),
+                    get(gl::STENCIL_FAIL),
+                    get(gl::STENCIL_PASS_DEPTH_FAIL),
+                    get(gl::STENCIL_PASS_DEPTH_PASS),
+                    get(gl::STENCIL_WRITEMASK),
+                ],
+                stencil_back: [
+                    get(gl::STENCIL_BACK_FUNC),
+                    get(gl::STENCIL_BACK_REF),
+                    get(gl::STENCIL_BACK_VALUE_MASK),
+                    get(gl::STENCIL_BACK_FAIL),
+                    get(gl::STENCIL_BACK_PASS_DEPTH_FAIL),
+                    get(gl::STENCIL_BACK_PASS_DEPTH_PASS),
+                    get(gl::STENCIL_BACK_WRITEMASK),
+                ],
+            }
+        }
+    }
+
+    fn restore(&self) {
+        fn set_enabled(cap: GLenum, enabled: bool) {
+            unsafe {
+                if enabled {
+                    gl::Enable(cap);
+                } else {
+                    gl::Disable(cap);
+                }
+            }
+        }
+
+        set_enabled(gl::DEPTH_TEST, self.depth_test);
+        set_enabled(gl::STENCIL_TEST, self.stencil_test);
+
+        unsafe {
+            gl::DepthFunc(self.depth_func as GLenum);
+            gl::DepthMask((self.depth_mask != 0) as _);
+
+            for (face, [func, reference, mask, fail, depth_fail, depth_pass, write_mask]) in [
+                (gl::FRONT, self.stencil_front),
+                (gl::BACK, self.stencil_back),
+            ] {
+                gl::StencilFuncSeparate(face, func as GLenum, reference, mask as GLuint);
+                gl::StencilOpSeparate(
+                    face,
+                    fail as GLenum,
+                    depth_fail as GLenum,
+                    depth_pass as GLenum,
+                );
+                gl::StencilMaskSeparate(face, write_mask as GLuint);
+            }
+        }
+    }
+}
+
+/// Storage format of a user texture.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TextureFormat {
+    /// Premultiplied sRGBA, 8 bits per channel, as used by [`Color32`].
+    #[default]
+    Rgba8,
+
+    /// Single 8-bit channel, displayed as opaque grey.
+    R8,
+
+    /// Two 8-bit channels, displayed as grey with the second channel as alpha.
+    Rg8,
+
+    /// Linear HDR color with straight alpha, stored as half floats
+    /// and tonemapped for display.
+    Rgba16F,
+
+    /// Linear HDR color with straight alpha, stored as floats
+    /// and tonemapped for display.
+    Rgba32F,
+}
+
+impl TextureFormat {
+    /// Number of channels of the CPU-side pixel data.
+    pub fn channels(self) -> usize {
+        match self {
+            TextureFormat::R8 => 1,
+            TextureFormat::Rg8 => 2,
+            TextureFormat::Rgba8 | TextureFormat::Rgba16F | TextureFormat::Rgba32F => 4,
+        }
+    }
+
+    /// Whether the format is uploaded from `f32` data.
+    pub fn is_float(self) -> bool {
+        matches!(self, TextureFormat::Rgba16F | TextureFormat::Rgba32F)
+    }
+
+    /// Bytes per pixel of the GPU storage.
+    fn gpu_bytes_per_pixel(self) -> usize {
+        match self {
+            TextureFormat::R8 => 1,
+            TextureFormat::Rg8 => 2,
+            TextureFormat::Rgba8 => 4,
+            TextureFormat::Rgba16F => 8,
+            TextureFormat::Rgba32F => 16,
+        }
+    }
+
+    /// Internal format, source format and source type for `glTexImage2D`.
+    fn gl_formats(self) -> (GLenum, GLenum, GLenum) {
+        match self {
+            TextureFormat::Rgba8 => (gl::RGBA, gl::RGBA, gl::UNSIGNED_BYTE),
+            TextureFormat::R8 => (gl::R8, gl::RED, gl::UNSIGNED_BYTE),
+            TextureFormat::Rg8 => (gl::RG8, gl::RG, gl::UNSIGNED_BYTE),
+            TextureFormat::Rgba16F => (gl::RGBA16F, gl::RGBA, gl::FLOAT),
+            TextureFormat::Rgba32F => (gl::RGBA32F, gl::RGBA, gl::FLOAT),
+        }
+    }
+
+    /// Swizzle applied when sampling, so that every format reads as RGBA.
+    fn gl_swizzle(self) -> Option<[GLenum; 4]> {
+        match self {
+            TextureFormat::R8 => Some([gl::RED, gl::RED, gl::RED, gl::ONE]),
+            TextureFormat::Rg8 => Some([gl::RED, gl::RED, gl::RED, gl::GREEN]),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct UserTexture {
     size: (usize, usize),
 
+    format: TextureFormat,
+
     /// Pending upload (will be emptied later).
     pixels: Vec<u8>,
 
@@ -107,6 +470,16 @@ pub struct UserTexture {
     /// is used to indicate if pixel data for the
     /// texture has been updated.
     dirty: bool,
+
+    /// Keep `pixels` after uploading, so the texture can be evicted
+    /// from GPU memory and re-uploaded when it is drawn again.
+    retained: bool,
+
+    /// GL storage was released by the texture budget.
+    evicted: bool,
+
+    /// Value of the frame counter when the texture was last drawn.
+    last_used: u64,
 }
 
 impl UserTexture {
@@ -148,6 +521,15 @@ impl UserTexture {
             filtering: TextureFilter::Linear,
             dirty: false,
             pixels: Vec::with_capacity(0),
+            ..Default::default()
+        }
+    }
+
+    /// Bytes of GPU memory used by the texture, if it is resident.
+    fn resident_bytes(&self) -> usize {
+        match self.gl_texture_id {
+            Some(_) => self.size.0 * self.size.1 * self.format.gpu_bytes_per_pixel(),
+            None => 0,
         }
     }
 
@@ -160,9 +542,121 @@ impl UserTexture {
     }
 }
 
-pub struct Painter {
+/// GL objects that can be shared between painters whose windows were created
+/// with a shared OpenGL context (see `glfw::Window::create_shared`).
+///
+/// Vertex array objects are container objects and can't be shared, so they
+/// stay owned by each [`Painter`].
+pub struct SharedResources {
     program: GLuint,
 
+    textures: HashMap<egui::TextureId, UserTexture>,
+
+    /// Present cycle, used to find least-recently-drawn textures. A cycle ends when a
+    /// painter paints again, so each window sharing these resources gets one paint per cycle.
+    frame: u64,
+
+    /// Painters that painted in the current cycle, see [`Painter::id`].
+    painted_this_frame: Vec<usize>,
+    next_painter_id: usize,
+
+    next_user_texture_id: u64,
+    texture_budget: Option<usize>,
+    texture_stats: TextureStats,
+}
+
+impl SharedResources {
+    /// Allocates an id for a new user texture. Ids are never reused, so freeing a texture
+    /// can't make a later one replace a texture that is still in use.
+    fn next_user_texture_id(&mut self) -> egui::TextureId {
+        self.next_user_texture_id += 1;
+        egui::TextureId::User(self.next_user_texture_id - 1)
+    }
+
+    /// Marks the textures painter `painter_id` is about to draw as drawn this cycle,
+    /// scheduling a re-upload for any of them that were evicted.
+    fn touch_textures(&mut self, painter_id: usize, clipped_primitives: &[egui::ClippedPrimitive]) {
+        if self.painted_this_frame.contains(&painter_id) {
+            self.frame += 1;
+            self.painted_this_frame.clear();
+        }
+        self.painted_this_frame.push(painter_id);
+
+        for clipped_primitive in clipped_primitives {
+            if let Primitive::Mesh(mesh) = &clipped_primitive.primitive {
+                if let Some(texture) = self.textures.get_mut(&mesh.texture_id) {
+                    texture.last_used = self.frame;
+
+                    if texture.evicted {
+                        texture.evicted = false;
+                        texture.dirty = true;
+                        self.texture_stats.reuploads += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Releases the GL storage of least-recently-drawn user textures until the resident
+    /// textures fit in the budget. Textures drawn by any painter this cycle are never evicted.
+    fn enforce_texture_budget(&mut self) {
+        let mut resident_bytes: usize = self.textures.values().map(|t| t.resident_bytes()).sum();
+        self.texture_stats.resident_bytes = resident_bytes;
+
+        let budget = match self.texture_budget {
+            Some(budget) if resident_bytes > budget => budget,
+            _ => return,
+        };
+
+        let mut candidates: Vec<_> = self
+            .textures
+            .iter()
+            .filter(|(id, texture)| {
+                matches!(id, egui::TextureId::User(_))
+                    && texture.retained
+                    && texture.gl_texture_id.is_some()
+                    && texture.last_used < self.frame
+            })
+            .map(|(id, texture)| (texture.last_used, *id))
+            .collect();
+        candidates.sort_unstable_by_key(|(last_used, _)| *last_used);
+
+        for (_, id) in candidates {
+            if resident_bytes <= budget {
+                break;
+            }
+
+            let texture = self.textures.get_mut(&id).unwrap();
+            resident_bytes -= texture.resident_bytes();
+            texture.delete();
+            texture.gl_texture_id = None;
+            texture.evicted = true;
+            self.texture_stats.evictions += 1;
+        }
+
+        self.texture_stats.resident_bytes = resident_bytes;
+    }
+}
+
+/// Statistics of the texture memory budget, see [`Painter::set_texture_budget`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TextureStats {
+    /// Estimated GPU memory used by resident textures after the last paint.
+    pub resident_bytes: usize,
+
+    /// Number of times a user texture was evicted from GPU memory.
+    pub evictions: u64,
+
+    /// Number of times an evicted user texture was uploaded again.
+    pub reuploads: u64,
+}
+
+pub struct Painter {
+    shared: Rc<RefCell<SharedResources>>,
+
+    /// Identifies the painter among those sharing resources.
+    id: usize,
+
     vertex_array: GLuint,
     index_buffer: GLuint,
     pos_buffer: GLuint,
@@ -171,21 +665,94 @@ pub struct Painter {
 
     canvas_width: u32,
     canvas_height: u32,
+    fixed_size: bool,
+
+    /// Program built from a user post-process snippet, used instead of the shared one.
+    post_process_program: Option<GLuint>,
+    uniforms: HashMap<CString, Uniform>,
+
+    transform: Transform2D,
 
-    textures: std::collections::HashMap<egui::TextureId, UserTexture>,
+    depth_mode: DepthMode,
+    stencil_mode: StencilMode,
+
+    hdr_exposure: f32,
 }
 
 impl Painter {
+    /// Sets the size in pixels of the target painted to, e.g. an offscreen framebuffer.
+    ///
+    /// By default the painter follows the framebuffer size of the current GLFW context,
+    /// calling this disables that until [`Painter::use_framebuffer_size`] is called.
     pub fn set_size(&mut self, w: u32, h: u32) {
         (self.canvas_width, self.canvas_height) = (w, h);
+        self.fixed_size = true;
+    }
+
+    /// Follow the framebuffer size of the current GLFW context again, see [`Painter::set_size`].
+    pub fn use_framebuffer_size(&mut self) {
+        self.fixed_size = false;
+    }
+
+    /// Size in pixels of the target painted to.
+    pub fn size(&self) -> (u32, u32) {
+        (self.canvas_width, self.canvas_height)
+    }
+
+    fn update_size(&mut self) {
+        if self.fixed_size {
+            return;
+        }
+
+        let (mut width, mut height) = (0, 0);
+        unsafe {
+            let window = glfw::ffi::glfwGetCurrentContext();
+            if !window.is_null() {
+                glfw::ffi::glfwGetFramebufferSize(window, &mut width, &mut height);
+            } else {
+                let mut viewport = [0; 4];
+                gl::GetIntegerv(gl::VIEWPORT, viewport.as_mut_ptr());
+                (width, height) = (viewport[2], viewport[3]);
+            }
+        }
+
+        // Minimized windows have an empty framebuffer, keep the last size for those.
+        if width > 0 && height > 0 {
+            (self.canvas_width, self.canvas_height) = (width as u32, height as u32);
+        }
     }
 
     pub fn new(window: &mut glfw::Window) -> Painter {
-        let vs = compile_shader(include_str!("shader/vertex.vert"), gl::VERTEX_SHADER);
-        let fs = compile_shader(include_str!("shader/fragment.frag"), gl::FRAGMENT_SHADER);
+        let program = build_program(include_str!("shader/post_process.frag"))
+            .unwrap_or_else(|err| panic!("{}", err));
 
-        let program = link_program(vs, fs);
+        let shared = Rc::new(RefCell::new(SharedResources {
+            program,
+            textures: Default::default(),
+            frame: 0,
+            painted_this_frame: Vec::new(),
+            next_painter_id: 0,
+            next_user_texture_id: 0,
+            texture_budget: None,
+            texture_stats: Default::default(),
+        }));
+
+        Self::with_shared_resources(window, shared)
+    }
 
+    /// Creates a painter for `window` that uses the program and textures of `other`.
+    ///
+    /// `window` must have been created as a shared context of the window `other` paints to,
+    /// and its context must be current. All painters sharing resources should be driven by
+    /// the same `egui::Context`, since egui-managed texture ids are only unique per context.
+    pub fn new_shared(window: &mut glfw::Window, other: &Painter) -> Painter {
+        Self::with_shared_resources(window, other.shared.clone())
+    }
+
+    fn with_shared_resources(
+        window: &mut glfw::Window,
+        shared: Rc<RefCell<SharedResources>>,
+    ) -> Painter {
         let mut vertex_array = 0;
         let mut index_buffer = 0;
         let mut pos_buffer = 0;
@@ -200,10 +767,17 @@ impl Painter {
             gl::GenBuffers(1, &mut color_buffer);
         }
 
-        let (canvas_width, canvas_height) = window.get_size();
+        let (canvas_width, canvas_height) = window.get_framebuffer_size();
+
+        let id = {
+            let mut shared = shared.borrow_mut();
+            shared.next_painter_id += 1;
+            shared.next_painter_id - 1
+        };
 
         Painter {
-            program,
+            shared,
+            id,
 
             vertex_array,
             index_buffer,
@@ -213,9 +787,158 @@ impl Painter {
 
             canvas_width: canvas_width as _,
             canvas_height: canvas_height as _,
+            fixed_size: false,
 
-            textures: Default::default(),
+            post_process_program: None,
+            uniforms: Default::default(),
+
+            transform: Transform2D::IDENTITY,
+
+            depth_mode: DepthMode::Disabled,
+            stencil_mode: StencilMode::Disabled,
+
+            hdr_exposure: 1.0,
+        }
+    }
+
+    /// Sets a transform applied to everything painted, in points.
+    ///
+    /// Clip rects are transformed to match. Rotations that aren't a multiple of 90° can't be
+    /// expressed as a scissor rect, so clipping falls back to the fragment shader for those.
+    /// Pointer input is not affected, use [`Transform2D::inverse`] to map it back.
+    pub fn set_transform(&mut self, transform: Transform2D) {
+        self.transform = transform;
+    }
+
+    pub fn transform(&self) -> Transform2D {
+        self.transform
+    }
+
+    /// Sets how the UI is depth tested, to composite it into a 3D scene.
+    ///
+    /// The application's depth and stencil state is restored after each paint.
+    pub fn set_depth_mode(&mut self, depth_mode: DepthMode) {
+        self.depth_mode = depth_mode;
+    }
+
+    pub fn depth_mode(&self) -> DepthMode {
+        self.depth_mode
+    }
+
+    /// Sets how the UI tests or writes the stencil buffer.
+    pub fn set_stencil_mode(&mut self, stencil_mode: StencilMode) {
+        self.stencil_mode = stencil_mode;
+    }
+
+    pub fn stencil_mode(&self) -> StencilMode {
+        self.stencil_mode
+    }
+
+    /// Sets the exposure applied before tonemapping float textures, 1.0 by default.
+    pub fn set_hdr_exposure(&mut self, exposure: f32) {
+        self.hdr_exposure = exposure;
+    }
+
+    /// Limits the GPU memory used by textures to roughly `budget` bytes, or removes the limit.
+    ///
+    /// When over budget, the least-recently-drawn user textures have their GL storage
+    /// released and are uploaded again from a CPU copy the next time they are drawn.
+    /// Only textures created with [`Painter::new_user_texture`] while a budget is set keep
+    /// such a copy and can be evicted. The budget is shared with painters created by
+    /// [`Painter::new_shared`], and a texture drawn by any of them is kept until each has
+    /// painted again, so windows that paint once per frame don't evict each other's textures.
+    pub fn set_texture_budget(&mut self, budget: Option<usize>) {
+        self.shared.borrow_mut().texture_budget = budget;
+    }
+
+    pub fn texture_stats(&self) -> TextureStats {
+        self.shared.borrow().texture_stats
+    }
+
+    fn apply_depth_stencil_state(&self) -> f32 {
+        let depth = match self.depth_mode {
+            DepthMode::Disabled => {
+                unsafe {
+                    gl::Disable(gl::DEPTH_TEST);
+                }
+                0.0
+            }
+
+            DepthMode::Test { depth, write } => {
+                unsafe {
+                    gl::Enable(gl::DEPTH_TEST);
+                    gl::DepthFunc(gl::LEQUAL);
+                    gl::DepthMask(write as _);
+                }
+                depth
+            }
+        };
+
+        match self.stencil_mode {
+            StencilMode::Disabled => unsafe {
+                gl::Disable(gl::STENCIL_TEST);
+            },
+
+            StencilMode::Test { reference, mask } => unsafe {
+                gl::Enable(gl::STENCIL_TEST);
+                gl::StencilFunc(gl::EQUAL, reference as GLint, mask as GLuint);
+                gl::StencilOp(gl::KEEP, gl::KEEP, gl::KEEP);
+                gl::StencilMask(0);
+            },
+
+            StencilMode::Write { reference, mask } => unsafe {
+                gl::Enable(gl::STENCIL_TEST);
+                gl::StencilFunc(gl::ALWAYS, reference as GLint, 0xff);
+                gl::StencilOp(gl::KEEP, gl::KEEP, gl::REPLACE);
+                gl::StencilMask(mask as GLuint);
+            },
         }
+
+        depth
+    }
+
+    /// Replaces the post-process step of the egui fragment shader for this painter.
+    ///
+    /// `source` is GLSL ES 1.00 that must define `vec4 post_process(vec4 color)`, which
+    /// receives the premultiplied output color of every painted fragment. It may declare its
+    /// own uniforms, which are set with [`Painter::set_uniform`].
+    ///
+    /// Returns the compile or link log if the shader is invalid, in which case the
+    /// previous post-process step is kept.
+    pub fn set_post_process_shader(&mut self, source: &str) -> Result<(), String> {
+        let program = build_program(source)?;
+
+        if let Some(previous) = self.post_process_program.replace(program) {
+            unsafe {
+                gl::DeleteProgram(previous);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Restores the default post-process step, which leaves colors untouched.
+    pub fn clear_post_process_shader(&mut self) {
+        if let Some(program) = self.post_process_program.take() {
+            unsafe {
+                gl::DeleteProgram(program);
+            }
+        }
+    }
+
+    /// Sets a uniform that is uploaded every frame before painting.
+    ///
+    /// Uniforms the current shader doesn't declare are ignored, so values can be set
+    /// before the post-process shader that uses them. Fails if `name` contains a NUL byte.
+    pub fn set_uniform(&mut self, name: &str, value: Uniform) -> Result<(), String> {
+        let name = CString::new(name).map_err(|err| err.to_string())?;
+        self.uniforms.insert(name, value);
+        Ok(())
+    }
+
+    fn program(&self) -> GLuint {
+        self.post_process_program
+            .unwrap_or_else(|| self.shared.borrow().program)
     }
 
     pub fn paint_and_update_textures(
@@ -241,7 +964,14 @@ impl Painter {
         pixels_per_point: f32,
         clipped_primitives: &[egui::ClippedPrimitive],
     ) {
+        self.update_size();
+        self.shared
+            .borrow_mut()
+            .touch_textures(self.id, clipped_primitives);
         self.upload_user_textures();
+        self.shared.borrow_mut().enforce_texture_budget();
+
+        let program = self.program();
 
         unsafe {
             //Let OpenGL know we are dealing with SRGB colors so that it
@@ -252,13 +982,13 @@ impl Painter {
             gl::Enable(gl::SCISSOR_TEST);
             gl::Enable(gl::BLEND);
             gl::BlendFunc(gl::ONE, gl::ONE_MINUS_SRC_ALPHA); // premultiplied alpha
-            gl::UseProgram(self.program);
+            gl::UseProgram(program);
             gl::ActiveTexture(gl::TEXTURE0);
         }
 
         let u_screen_size = CString::new("u_screen_size").unwrap();
         let u_screen_size_ptr = u_screen_size.as_ptr();
-        let u_screen_size_loc = unsafe { gl::GetUniformLocation(self.program, u_screen_size_ptr) };
+        let u_screen_size_loc = unsafe { gl::GetUniformLocation(program, u_screen_size_ptr) };
         let screen_size_pixels = egui::vec2(self.canvas_width as f32, self.canvas_height as f32);
         let screen_size_points = screen_size_pixels / pixels_per_point;
 
@@ -272,12 +1002,39 @@ impl Painter {
 
         let u_sampler = CString::new("u_sampler").unwrap();
         let u_sampler_ptr = u_sampler.as_ptr();
-        let u_sampler_loc = unsafe { gl::GetUniformLocation(self.program, u_sampler_ptr) };
+        let u_sampler_loc = unsafe { gl::GetUniformLocation(program, u_sampler_ptr) };
         unsafe {
             gl::Uniform1i(u_sampler_loc, 0);
             gl::Viewport(0, 0, self.canvas_width as i32, self.canvas_height as i32);
         }
 
+        let saved_depth_stencil_state = DepthStencilState::save();
+        let depth = self.apply_depth_stencil_state();
+
+        let u_depth = CString::new("u_depth").unwrap();
+        let u_depth_loc = unsafe { gl::GetUniformLocation(program, u_depth.as_ptr()) };
+        unsafe {
+            gl::Uniform1f(u_depth_loc, depth);
+        }
+
+        let u_transform = CString::new("u_transform").unwrap();
+        let u_transform_loc = unsafe { gl::GetUniformLocation(program, u_transform.as_ptr()) };
+        unsafe {
+            gl::UniformMatrix3fv(
+                u_transform_loc,
+                1,
+                gl::FALSE,
+                self.transform.to_mat3().as_ptr(),
+            );
+        }
+
+        for (name, value) in &self.uniforms {
+            let location = unsafe { gl::GetUniformLocation(program, name.as_ptr()) };
+            if location >= 0 {
+                value.apply(location);
+            }
+        }
+
         for egui::ClippedPrimitive {
             clip_rect,
             primitive,
@@ -300,12 +1057,15 @@ impl Painter {
         unsafe {
             gl::Disable(gl::FRAMEBUFFER_SRGB);
         }
+
+        saved_depth_stencil_state.restore();
     }
 
     pub fn new_opengl_texture(&mut self, openl_id: u32) -> egui::TextureId {
-        let id = egui::TextureId::User(self.textures.len() as u64);
+        let mut shared = self.shared.borrow_mut();
+        let id = shared.next_user_texture_id();
 
-        self.textures.insert(id, UserTexture::from_raw(openl_id));
+        shared.textures.insert(id, UserTexture::from_raw(openl_id));
 
         id
     }
@@ -319,16 +1079,60 @@ impl Painter {
         assert_eq!(size.0 * size.1, srgba_pixels.len());
 
         let pixels: Vec<u8> = srgba_pixels.iter().flat_map(|a| a.to_array()).collect();
-        let id = egui::TextureId::User(self.textures.len() as u64);
+        self.insert_user_texture(size, TextureFormat::Rgba8, pixels, filtering)
+    }
 
-        self.textures.insert(
+    /// Creates a user texture in an 8-bit `format` from tightly packed channel data.
+    pub fn new_user_texture_u8(
+        &mut self,
+        size: (usize, usize),
+        format: TextureFormat,
+        pixels: &[u8],
+        filtering: TextureFilter,
+    ) -> egui::TextureId {
+        assert!(!format.is_float(), "{:?} expects f32 pixel data", format);
+        assert_eq!(size.0 * size.1 * format.channels(), pixels.len());
+
+        self.insert_user_texture(size, format, pixels.to_vec(), filtering)
+    }
+
+    /// Creates a user texture in a float `format` from RGBA channel data.
+    pub fn new_user_texture_f32(
+        &mut self,
+        size: (usize, usize),
+        format: TextureFormat,
+        pixels: &[f32],
+        filtering: TextureFilter,
+    ) -> egui::TextureId {
+        assert!(format.is_float(), "{:?} expects u8 pixel data", format);
+        assert_eq!(size.0 * size.1 * format.channels(), pixels.len());
+
+        let pixels = pixels.iter().flat_map(|a| a.to_ne_bytes()).collect();
+        self.insert_user_texture(size, format, pixels, filtering)
+    }
+
+    fn insert_user_texture(
+        &mut self,
+        size: (usize, usize),
+        format: TextureFormat,
+        pixels: Vec<u8>,
+        filtering: TextureFilter,
+    ) -> egui::TextureId {
+        let mut shared = self.shared.borrow_mut();
+        let retained = shared.texture_budget.is_some();
+        let id = shared.next_user_texture_id();
+
+        shared.textures.insert(
             id,
             UserTexture {
                 size,
+                format,
                 pixels,
                 gl_texture_id: None,
                 filtering,
                 dirty: true,
+                retained,
+                ..Default::default()
             },
         );
 
@@ -336,19 +1140,57 @@ impl Painter {
     }
 
     pub fn update_user_texture_data(&mut self, texture_id: &egui::TextureId, pixels: &[Color32]) {
-        let texture = self
+        self.update_user_texture(texture_id, |texture| {
+            assert_eq!(texture.format, TextureFormat::Rgba8);
+            assert_eq!(texture.size.0 * texture.size.1, pixels.len());
+            pixels.iter().flat_map(|a| a.to_array()).collect()
+        });
+    }
+
+    pub fn update_user_texture_u8(&mut self, texture_id: &egui::TextureId, pixels: &[u8]) {
+        self.update_user_texture(texture_id, |texture| {
+            assert!(!texture.format.is_float());
+            assert_eq!(
+                texture.size.0 * texture.size.1 * texture.format.channels(),
+                pixels.len()
+            );
+            pixels.to_vec()
+        });
+    }
+
+    pub fn update_user_texture_f32(&mut self, texture_id: &egui::TextureId, pixels: &[f32]) {
+        self.update_user_texture(texture_id, |texture| {
+            assert!(texture.format.is_float());
+            assert_eq!(
+                texture.size.0 * texture.size.1 * texture.format.channels(),
+                pixels.len()
+            );
+            pixels.iter().flat_map(|a| a.to_ne_bytes()).collect()
+        });
+    }
+
+    fn update_user_texture(
+        &mut self,
+        texture_id: &egui::TextureId,
+        pixels: impl FnOnce(&UserTexture) -> Vec<u8>,
+    ) {
+        let mut shared = self.shared.borrow_mut();
+        let texture = shared
             .textures
             .get_mut(texture_id)
             .expect("Texture with id has not been created");
 
-        texture.pixels = pixels.iter().flat_map(|a| a.to_array()).collect();
+        texture.pixels = pixels(texture);
         texture.dirty = true;
     }
 
     fn paint_mesh(&self, mesh: &Mesh, clip_rect: &Rect, pixels_per_point: f32) {
         debug_assert!(mesh.is_valid());
 
-        if let Some(it) = self.textures.get(&mesh.texture_id) {
+        let program = self.program();
+        let shared = self.shared.borrow();
+
+        if let Some(it) = shared.textures.get(&mesh.texture_id) {
             unsafe {
                 gl::BindTexture(
                     gl::TEXTURE_2D,
@@ -357,9 +1199,37 @@ impl Painter {
                 );
             }
 
+            let u_hdr = CString::new("u_hdr").unwrap();
+            let u_exposure = CString::new("u_exposure").unwrap();
+            unsafe {
+                let u_hdr_loc = gl::GetUniformLocation(program, u_hdr.as_ptr());
+                let u_exposure_loc = gl::GetUniformLocation(program, u_exposure.as_ptr());
+                gl::Uniform1i(u_hdr_loc, it.format.is_float() as GLint);
+                gl::Uniform1f(u_exposure_loc, self.hdr_exposure);
+            }
+
             let screen_size_pixels =
                 egui::vec2(self.canvas_width as f32, self.canvas_height as f32);
 
+            let shader_clip = !self.transform.is_axis_aligned();
+
+            let u_shader_clip = CString::new("u_shader_clip").unwrap();
+            let u_clip_rect = CString::new("u_clip_rect").unwrap();
+            unsafe {
+                let u_shader_clip_loc = gl::GetUniformLocation(program, u_shader_clip.as_ptr());
+                let u_clip_rect_loc = gl::GetUniformLocation(program, u_clip_rect.as_ptr());
+                gl::Uniform1i(u_shader_clip_loc, shader_clip as GLint);
+                gl::Uniform4f(
+                    u_clip_rect_loc,
+                    clip_rect.min.x,
+                    clip_rect.min.y,
+                    clip_rect.max.x,
+                    clip_rect.max.y,
+                );
+            }
+
+            let clip_rect = self.transform.transform_rect(clip_rect);
+
             let clip_min_x = pixels_per_point * clip_rect.min.x;
             let clip_min_y = pixels_per_point * clip_rect.min.y;
             let clip_max_x = pixels_per_point * clip_rect.max.x;
@@ -375,6 +1245,7 @@ impl Painter {
 
             //scissor Y coordinate is from the bottom
             unsafe {
+                gl::Enable(gl::SCISSOR_TEST);
                 gl::Scissor(
                     clip_min_x,
                     self.canvas_height as i32 - clip_max_y,
@@ -428,7 +1299,7 @@ impl Painter {
 
             let a_pos = CString::new("a_pos").unwrap();
             let a_pos_ptr = a_pos.as_ptr();
-            let a_pos_loc = unsafe { gl::GetAttribLocation(self.program, a_pos_ptr) };
+            let a_pos_loc = unsafe { gl::GetAttribLocation(program, a_pos_ptr) };
             assert!(a_pos_loc >= 0);
             let a_pos_loc = a_pos_loc as u32;
 
@@ -456,7 +1327,7 @@ impl Painter {
 
             let a_tc = CString::new("a_tc").unwrap();
             let a_tc_ptr = a_tc.as_ptr();
-            let a_tc_loc = unsafe { gl::GetAttribLocation(self.program, a_tc_ptr) };
+            let a_tc_loc = unsafe { gl::GetAttribLocation(program, a_tc_ptr) };
             assert!(a_tc_loc >= 0);
             let a_tc_loc = a_tc_loc as u32;
 
@@ -484,7 +1355,7 @@ impl Painter {
 
             let a_srgba = CString::new("a_srgba").unwrap();
             let a_srgba_ptr = a_srgba.as_ptr();
-            let a_srgba_loc = unsafe { gl::GetAttribLocation(self.program, a_srgba_ptr) };
+            let a_srgba_loc = unsafe { gl::GetAttribLocation(program, a_srgba_ptr) };
             assert!(a_srgba_loc >= 0);
             let a_srgba_loc = a_srgba_loc as u32;
 
@@ -517,7 +1388,7 @@ impl Painter {
         let [w, h] = delta.image.size();
 
         if let Some([x, y]) = delta.pos {
-            if let Some(texture) = self.textures.get_mut(&tex_id) {
+            if let Some(texture) = self.shared.borrow_mut().textures.get_mut(&tex_id) {
                 match &delta.image {
                     egui::ImageData::Color(image) => {
                         assert_eq!(
@@ -568,6 +1439,7 @@ impl Painter {
                         gl_texture_id: None,
                         filtering: TextureFilter::Linear,
                         dirty: true,
+                        ..Default::default()
                     }
                 }
                 egui::ImageData::Font(image) => {
@@ -589,11 +1461,12 @@ impl Painter {
                         gl_texture_id: None,
                         filtering: TextureFilter::Linear,
                         dirty: true,
+                        ..Default::default()
                     }
                 }
             };
 
-            let previous = self.textures.insert(tex_id, texture);
+            let previous = self.shared.borrow_mut().textures.insert(tex_id, texture);
             if let Some(previous) = previous {
                 previous.delete();
             }
@@ -601,9 +1474,14 @@ impl Painter {
     }
 
     fn upload_user_textures(&mut self) {
-        self.textures
+        self.shared
+            .borrow_mut()
+            .textures
             .values_mut()
-            .filter(|user_texture| user_texture.gl_texture_id.is_none() || user_texture.dirty)
+            .filter(|user_texture| {
+                !user_texture.evicted
+                    && (user_texture.gl_texture_id.is_none() || user_texture.dirty)
+            })
             .for_each(|user_texture| {
                 let pixels = std::mem::take(&mut user_texture.pixels);
 
@@ -656,17 +1534,27 @@ impl Painter {
                                 );
                             },
                         }
+
+                        if let Some([r, g, b, a]) = user_texture.format.gl_swizzle() {
+                            unsafe {
+                                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_SWIZZLE_R, r as _);
+                                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_SWIZZLE_G, g as _);
+                                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_SWIZZLE_B, b as _);
+                                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_SWIZZLE_A, a as _);
+                            }
+                        }
+
                         user_texture.gl_texture_id = Some(gl_texture);
                     }
                 }
 
                 if !pixels.is_empty() {
                     let level = 0;
-                    let internal_format = gl::RGBA;
+                    let (internal_format, src_format, src_type) = user_texture.format.gl_formats();
                     let border = 0;
-                    let src_format = gl::RGBA;
-                    let src_type = gl::UNSIGNED_BYTE;
                     unsafe {
+                        // Rows of single and dual channel textures aren't 4-byte aligned.
+                        gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
                         gl::TexImage2D(
                             gl::TEXTURE_2D,
                             level,
@@ -681,13 +1569,154 @@ impl Painter {
                     }
                 }
 
+                if user_texture.retained {
+                    user_texture.pixels = pixels;
+                }
+
                 user_texture.dirty = false;
             });
     }
 
     pub fn free_texture(&mut self, tex_id: egui::TextureId) {
-        if let Some(old_tex) = self.textures.remove(&tex_id) {
+        let removed = self.shared.borrow_mut().textures.remove(&tex_id);
+        if let Some(old_tex) = removed {
             old_tex.delete();
         }
     }
 }
+
+/// How decoded images are converted into texture data, see [`Painter::new_texture_from_image_bytes`].
+#[cfg(feature = "image")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ImageOptions {
+    pub filtering: TextureFilter,
+
+    /// The decoded image has straight alpha and must be premultiplied, as egui expects.
+    /// Disable for images that are already premultiplied.
+    pub premultiply_alpha: bool,
+
+    /// The decoded color channels are sRGB encoded, which is the case for almost all
+    /// PNG, JPEG, BMP and WebP files. Disable to treat them as linear and convert to sRGB.
+    pub srgb: bool,
+}
+
+#[cfg(feature = "image")]
+impl Default for ImageOptions {
+    fn default() -> Self {
+        Self {
+            filtering: TextureFilter::Linear,
+            premultiply_alpha: true,
+            srgb: true,
+        }
+    }
+}
+
+#[cfg(feature = "image")]
+impl Painter {
+    /// Decodes a PNG, JPEG, BMP or WebP image and creates a user texture from it.
+    ///
+    /// Returns the texture id along with the image size in pixels.
+    pub fn new_texture_from_image_bytes(
+        &mut self,
+        bytes: &[u8],
+        options: ImageOptions,
+    ) -> image::ImageResult<(egui::TextureId, (usize, usize))> {
+        let image = image::load_from_memory(bytes)?;
+        Ok(self.new_texture_from_image(&image, options))
+    }
+
+    /// Like [`Painter::new_texture_from_image_bytes`], reading the image from `path`.
+    /// The format is guessed from the file contents rather than its extension.
+    pub fn new_texture_from_image_path(
+        &mut self,
+        path: impl AsRef<std::path::Path>,
+        options: ImageOptions,
+    ) -> image::ImageResult<(egui::TextureId, (usize, usize))> {
+        let image = image::io::Reader::open(path)?
+            .with_guessed_format()?
+            .decode()?;
+        Ok(self.new_texture_from_image(&image, options))
+    }
+
+    fn new_texture_from_image(
+        &mut self,
+        image: &image::DynamicImage,
+        options: ImageOptions,
+    ) -> (egui::TextureId, (usize, usize)) {
+        use egui::epaint::color::{gamma_u8_from_linear_f32, linear_f32_from_linear_u8};
+
+        let image = image.to_rgba8();
+        let size = (image.width() as usize, image.height() as usize);
+
+        let pixels: Vec<Color32> = image
+            .pixels()
+            .map(|&image::Rgba([r, g, b, a])| {
+                let [r, g, b] = if options.srgb {
+                    [r, g, b]
+                } else {
+                    [r, g, b].map(|c| gamma_u8_from_linear_f32(linear_f32_from_linear_u8(c)))
+                };
+
+                if options.premultiply_alpha {
+                    Color32::from_rgba_unmultiplied(r, g, b, a)
+                } else {
+                    Color32::from_rgba_premultiplied(r, g, b, a)
+                }
+            })
+            .collect();
+
+        let id = self.new_user_texture(size, &pixels, options.filtering);
+        (id, size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    fn assert_approx_eq(a: Transform2D, b: Transform2D) {
+        let close = |a: Vec2, b: Vec2| (a - b).length() < 1e-5;
+        assert!(
+            close(a.x_axis, b.x_axis)
+                && close(a.y_axis, b.y_axis)
+                && close(a.translation, b.translation),
+            "{:?} != {:?}",
+            a,
+            b
+        );
+    }
+
+    #[test]
+    fn inverse_undoes_transform() {
+        let t = Transform2D::from_scale(Vec2::new(2.0, 0.5))
+            .then(&Transform2D::from_rotation(0.3))
+            .then(&Transform2D::from_translation(Vec2::new(10.0, -4.0)));
+
+        assert_approx_eq(t.then(&t.inverse().unwrap()), Transform2D::IDENTITY);
+        assert_approx_eq(t.inverse().unwrap().then(&t), Transform2D::IDENTITY);
+
+        assert!(Transform2D::from_scale(Vec2::new(0.0, 1.0))
+            .inverse()
+            .is_none());
+    }
+
+    #[test]
+    fn quarter_turns_are_axis_aligned() {
+        assert!(Transform2D::IDENTITY.is_axis_aligned());
+        assert!(Transform2D::from_rotation(FRAC_PI_2).is_axis_aligned());
+        assert!(Transform2D::from_rotation(PI).is_axis_aligned());
+        assert!(!Transform2D::from_rotation(0.3).is_axis_aligned());
+    }
+
+    #[test]
+    fn transforms_rect_to_its_bounding_box() {
+        let rect = Rect::from_min_max(Pos2::new(0.0, 0.0), Pos2::new(4.0, 2.0));
+        let t = Transform2D::from_rotation(FRAC_PI_2)
+            .then(&Transform2D::from_translation(Vec2::new(10.0, 0.0)));
+
+        let transformed = t.transform_rect(&rect);
+        assert!((transformed.min - Pos2::new(8.0, 0.0)).length() < 1e-5);
+        assert!((transformed.max - Pos2::new(10.0, 4.0)).length() < 1e-5);
+    }
+}
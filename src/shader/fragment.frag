@@ -4,6 +4,16 @@ uniform sampler2D u_sampler;
 
 precision highp float;
 
+// Clip rect in untransformed points (min.x, min.y, max.x, max.y), used
+// when the transform rotates the UI and a scissor rect can't express it.
+uniform bool u_shader_clip;
+uniform vec4 u_clip_rect;
+
+// Float textures hold linear, straight alpha HDR colors that are tonemapped for display.
+uniform bool u_hdr;
+uniform float u_exposure;
+
+varying vec2 v_pos;
 varying vec2 v_tc;
 varying vec4 v_rgba;
 
@@ -33,9 +43,28 @@ vec4 srgba_from_linear(vec4 rgba) {
     return vec4(srgb_from_linear(rgba.rgb), 255.0 * rgba.a);
 }
 
-void main() {
+// 0-1 linear premultiplied  from  the bound texture
+vec4 sample_texture() {
+    vec4 texel = texture2D(u_sampler, v_tc);
+    if (u_hdr) {
+        // Reinhard tonemapping
+        vec3 rgb = max(texel.rgb, vec3(0.0)) * u_exposure;
+        rgb = rgb / (rgb + vec3(1.0));
+        float a = clamp(texel.a, 0.0, 1.0);
+        return vec4(rgb * a, a);
+    }
     // We must decode the colors, since WebGL1 doesn't come with sRGBA textures:
-    vec4 texture_rgba = linear_from_srgba(texture2D(u_sampler, v_tc) * 255.0);
+    return linear_from_srgba(texel * 255.0);
+}
+
+// @post_process
+
+void main() {
+    if (u_shader_clip && (any(lessThan(v_pos, u_clip_rect.xy)) || any(greaterThan(v_pos, u_clip_rect.zw)))) {
+        discard;
+    }
+
+    vec4 texture_rgba = sample_texture();
     // WebGL1 doesn't support linear blending in the framebuffer,
     // so we do a hack here where we change the premultiplied alpha
     // to do the multiplication in gamma space instead:
@@ -52,5 +81,5 @@ void main() {
         texture_rgba.rgb *= texture_rgba.a;
     }
     /// Multiply vertex color with texture color (in linear space).
-    gl_FragColor = v_rgba * texture_rgba;
+    gl_FragColor = post_process(v_rgba * texture_rgba);
 }
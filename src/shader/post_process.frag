@@ -0,0 +1,5 @@
+// Default post-process hook, replaced by `Painter::set_post_process_shader`.
+// `color` is the premultiplied output color of the egui pass.
+vec4 post_process(vec4 color) {
+    return color;
+}
@@ -1,11 +1,14 @@
 #version 100
 
 uniform vec2 u_screen_size;
+uniform mat3 u_transform;
+uniform float u_depth;
 
 attribute vec2 a_pos;
 attribute vec2 a_tc;
 attribute vec4 a_srgba;
 
+varying vec2 v_pos;
 varying vec2 v_tc;
 varying vec4 v_rgba;
 
@@ -38,11 +41,13 @@ vec4 srgba_from_linear(vec4 rgba) {
 }
 
 void main() {
+    vec2 pos = (u_transform * vec3(a_pos, 1.0)).xy;
     gl_Position = vec4(
-        2.0 * a_pos.x / u_screen_size.x - 1.0,
-        1.0 - 2.0 * a_pos.y / u_screen_size.y,
-        0.0,
+        2.0 * pos.x / u_screen_size.x - 1.0,
+        1.0 - 2.0 * pos.y / u_screen_size.y,
+        u_depth,
     1.0);
+    v_pos = a_pos;
     v_tc = a_tc;
     v_rgba = linear_from_srgba(a_srgba);
     v_rgba.a = pow(v_rgba.a, 1.6);